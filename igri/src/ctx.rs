@@ -0,0 +1,206 @@
+/*!
+Inspection context threaded through `Inspect::inspect_ctx` calls
+*/
+
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+    fmt,
+};
+
+use crate::{
+    filter::{Filter, Visibility, Within},
+    path::{FieldPath, Segment},
+    Inspect,
+};
+
+/// State of an inspection: the current field path, read-only mode, search filter and user data
+///
+/// Create one per root inspection (per frame), or just call `Inspect::inspect`, which creates a
+/// default context.
+#[derive(Default)]
+pub struct InspectCtx {
+    path: FieldPath,
+    read_only: bool,
+    filter: Option<Filter>,
+    /// Depth of the matched node being inspected. Its descendants are shown without filtering
+    matched_depth: Option<usize>,
+    /// Path → matches within the value, found once per root inspection
+    matches: HashMap<String, Within>,
+    /// User data keyed by type
+    data: HashMap<TypeId, Box<dyn Any>>,
+}
+
+impl fmt::Debug for InspectCtx {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("InspectCtx")
+            .field("path", &self.path)
+            .field("read_only", &self.read_only)
+            .field("filter", &self.filter)
+            .finish_non_exhaustive()
+    }
+}
+
+impl InspectCtx {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Path of the value being inspected, e.g. `entities[1].hp`
+    pub fn path(&self) -> &str {
+        self.path.as_str()
+    }
+
+    /// Number of segments of the current path. The root value is at depth `1`
+    pub fn depth(&self) -> usize {
+        self.path.depth()
+    }
+
+    /// If editing is disabled
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    pub fn set_read_only(&mut self, read_only: bool) {
+        self.read_only = read_only;
+    }
+
+    pub fn filter(&self) -> Option<&Filter> {
+        self.filter.as_ref()
+    }
+
+    /// Sets the search filter (see [`crate::filter`])
+    pub fn set_filter(&mut self, filter: Option<Filter>) {
+        self.filter = filter;
+        self.matched_depth = None;
+        self.matches.clear();
+    }
+
+    /// Visibility of the value at the current path under the filter
+    ///
+    /// Without the value, only the label and the field name are matched, and the branch is shown.
+    /// Values without [`Inspect::visit`] are shown, too, as their contents are unknown.
+    pub fn visibility(&mut self, label: &str, x: Option<&mut dyn Inspect>) -> Visibility {
+        self.visibility_by(label, |ctx, filter| match x {
+            Some(x) => match ctx.within(filter, x) {
+                Within::Match => Visibility::Ancestor,
+                Within::NoMatch => Visibility::Hidden,
+                Within::Unknown => Visibility::Shown,
+            },
+            None => Visibility::Shown,
+        })
+    }
+
+    /// If the value at the current path is hidden by the filter
+    pub fn is_hidden(&mut self, label: &str, x: &mut dyn Inspect) -> bool {
+        self.visibility(label, Some(x)) == Visibility::Hidden
+    }
+
+    /// If the value at the current path is hidden by the filter, matching the label and the field
+    /// name only. For values that are drawn without the standard inspectors
+    pub fn is_label_hidden(&mut self, label: &str) -> bool {
+        self.visibility_by(label, |_ctx, _filter| Visibility::Hidden) == Visibility::Hidden
+    }
+
+    /// Visibility by the label and the field name, or by the closure if they don't match
+    fn visibility_by(
+        &mut self,
+        label: &str,
+        unmatched: impl FnOnce(&mut Self, &Filter) -> Visibility,
+    ) -> Visibility {
+        let filter = match self.filter.take() {
+            Some(filter) if filter.is_active() => filter,
+            filter => {
+                self.filter = filter;
+                return Visibility::Shown;
+            }
+        };
+
+        let visibility = self.visibility_in(&filter, label, unmatched);
+        self.filter = Some(filter);
+        visibility
+    }
+
+    fn visibility_in(
+        &mut self,
+        filter: &Filter,
+        label: &str,
+        unmatched: impl FnOnce(&mut Self, &Filter) -> Visibility,
+    ) -> Visibility {
+        // inspection is depth-first, so we've left the matched branch if we're not deeper than it
+        match self.matched_depth {
+            Some(depth) if self.depth() > depth => return Visibility::Shown,
+            Some(_) => self.matched_depth = None,
+            None => {}
+        }
+
+        if filter.matches(label) || filter.matches(self.path.last()) {
+            self.matched_depth = Some(self.depth());
+            return Visibility::Matched;
+        }
+
+        unmatched(self, filter)
+    }
+
+    /// Matches within the value at the current path. The first query in a branch visits the whole
+    /// value, and the rest are looked up
+    fn within(&mut self, filter: &Filter, x: &mut dyn Inspect) -> Within {
+        if let Some(within) = self.matches.get(self.path.as_str()) {
+            return *within;
+        }
+
+        filter.find_within(self.path.as_str(), x, &mut self.matches);
+        self.matches
+            .get(self.path.as_str())
+            .copied()
+            .unwrap_or(Within::Unknown)
+    }
+
+    /// Inserts user data, returning the old value of the type
+    pub fn insert<T: Any>(&mut self, x: T) -> Option<T> {
+        let old = self.data.insert(TypeId::of::<T>(), Box::new(x))?;
+        old.downcast().ok().map(|x| *x)
+    }
+
+    pub fn get<T: Any>(&self) -> Option<&T> {
+        self.data.get(&TypeId::of::<T>())?.downcast_ref()
+    }
+
+    pub fn get_mut<T: Any>(&mut self) -> Option<&mut T> {
+        self.data.get_mut(&TypeId::of::<T>())?.downcast_mut()
+    }
+
+    pub fn remove<T: Any>(&mut self) -> Option<T> {
+        let x = self.data.remove(&TypeId::of::<T>())?;
+        x.downcast().ok().map(|x| *x)
+    }
+
+    /// Runs the closure with the segment pushed to the current path
+    pub fn scope<R>(&mut self, segment: Segment, f: impl FnOnce(&mut Self) -> R) -> R {
+        self.path.push(segment);
+        let ret = f(self);
+        self.path.pop();
+        ret
+    }
+
+    /// Runs the closure with `.name` pushed to the current path
+    pub fn field<R>(&mut self, name: &str, f: impl FnOnce(&mut Self) -> R) -> R {
+        self.scope(Segment::Field(name), f)
+    }
+
+    /// Runs the closure with `[i]` pushed to the current path
+    pub fn index<R>(&mut self, i: usize, f: impl FnOnce(&mut Self) -> R) -> R {
+        self.scope(Segment::Index(i), f)
+    }
+
+    /// Runs the closure with the label as the root segment, if no inspection is in progress
+    pub fn root<R>(&mut self, label: &str, f: impl FnOnce(&mut Self) -> R) -> R {
+        if self.path.is_empty() {
+            // the values may have changed since the last root inspection
+            self.matches.clear();
+            self.field(label, f)
+        } else {
+            f(self)
+        }
+    }
+}
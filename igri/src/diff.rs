@@ -0,0 +1,222 @@
+/*!
+Snapshot diff: what changed since a checkpoint, and side-by-side compare of two snapshots
+
+Take a [`Snapshot`] of a value as a checkpoint, and inspect the live value with [`inspect_diff`]:
+changed fields are highlighted, and their old values are shown as tooltips. [`compare`] shows two
+snapshots of the same type side by side.
+*/
+
+use std::{cell::RefCell, collections::HashMap, fmt, marker::PhantomData};
+
+use imgui::{TableFlags, Ui};
+
+use crate::{path, visit, visit::Leaf, Inspect, InspectCtx};
+
+/// Color of the changed items
+pub const CHANGED_COLOR: [f32; 4] = [0.4, 0.8, 1.0, 1.0];
+
+thread_local! {
+    /// Compare table ID → show only the changed leaves
+    static ONLY_CHANGES: RefCell<HashMap<String, bool>> = RefCell::new(HashMap::new());
+}
+
+/// Leaf values of a value of type `T` at a point of time
+pub struct Snapshot<T: ?Sized> {
+    entries: visit::Entries,
+    _ty: PhantomData<fn(&T)>,
+}
+
+impl<T: ?Sized> Clone for Snapshot<T> {
+    fn clone(&self) -> Self {
+        Self {
+            entries: self.entries.clone(),
+            _ty: PhantomData,
+        }
+    }
+}
+
+impl<T: ?Sized> fmt::Debug for Snapshot<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Snapshot")
+            .field("entries", &self.entries)
+            .finish()
+    }
+}
+
+impl<T: Inspect + ?Sized> Snapshot<T> {
+    /// Takes a snapshot of the leaf values (see [`crate::visit`])
+    pub fn take(x: &mut T) -> Self {
+        Self {
+            entries: visit::collect(x),
+            _ty: PhantomData,
+        }
+    }
+
+    /// Restores the leaf values. Returns the number of the applied leaves
+    pub fn restore(&self, x: &mut T) -> usize {
+        visit::apply(x, &self.entries)
+    }
+
+    /// Leaves that differ from the value
+    pub fn changes_in(&self, x: &mut T) -> Vec<Change> {
+        self.changes(&Self::take(x))
+    }
+}
+
+impl<T: ?Sized> Snapshot<T> {
+    /// `(relative leaf path, text)` pairs
+    pub fn entries(&self) -> &[(String, String)] {
+        &self.entries
+    }
+
+    /// Leaves that differ in the other snapshot, in the visit order
+    pub fn changes(&self, other: &Self) -> Vec<Change> {
+        self::merge(&self.entries, &other.entries)
+            .into_iter()
+            .filter(|(_, old, new)| old != new)
+            .map(|(path, old, new)| Change { path, old, new })
+            .collect()
+    }
+}
+
+/// Change of a leaf value between two snapshots
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Change {
+    /// Relative path of the leaf
+    pub path: String,
+    /// `None` if the leaf is added
+    pub old: Option<String>,
+    /// `None` if the leaf is removed
+    pub new: Option<String>,
+}
+
+/// Leaf values of the checkpoint (user data of the [`InspectCtx`])
+#[derive(Debug)]
+struct Since {
+    /// Path of the inspected root
+    base: String,
+    /// Relative path → text
+    old: HashMap<String, String>,
+}
+
+/// Standard method to inspect the value with the changes since the checkpoint highlighted
+pub fn inspect_diff<T: Inspect + ?Sized>(
+    x: &mut T,
+    since: &Snapshot<T>,
+    ctx: &mut InspectCtx,
+    ui: &Ui,
+    label: &str,
+) {
+    ctx.root(label, |ctx| {
+        let since = Since {
+            base: ctx.path().to_string(),
+            old: since.entries.iter().cloned().collect(),
+        };
+        let outer = ctx.insert(since);
+        x.inspect_ctx(ctx, ui, label);
+        ctx.remove::<Since>();
+        if let Some(outer) = outer {
+            ctx.insert(outer);
+        }
+    });
+}
+
+/// Old value of the leaf at the current path if it's changed since the checkpoint (`"<none>"` if
+/// the leaf is added)
+///
+/// The text of the leaf is made only while inspecting with a checkpoint.
+pub(crate) fn old_text(ctx: &InspectCtx, x: &dyn Leaf) -> Option<String> {
+    let since = ctx.get::<Since>()?;
+    let rel = path::strip(&since.base, ctx.path())?;
+    match since.old.get(rel) {
+        Some(old) if *old == x.to_text() => None,
+        Some(old) => Some(old.clone()),
+        None => Some("<none>".to_string()),
+    }
+}
+
+/// If any leaf under the current path is changed since the checkpoint
+pub(crate) fn is_changed(ctx: &InspectCtx, x: &mut dyn Inspect) -> bool {
+    let since = match ctx.get::<Since>() {
+        Some(since) => since,
+        None => return false,
+    };
+    let base = match path::strip(&since.base, ctx.path()) {
+        Some(base) => base,
+        None => return false,
+    };
+
+    let new = visit::collect(x);
+    let n_old = since
+        .old
+        .keys()
+        .filter(|p| path::strip(base, p).is_some())
+        .count();
+
+    n_old != new.len()
+        || new
+            .iter()
+            .any(|(rel, text)| since.old.get(&path::join(base, rel)) != Some(text))
+}
+
+/// Shows the two snapshots side by side, highlighting the differences
+pub fn compare<T: ?Sized>(ui: &Ui, id: &str, before: &Snapshot<T>, after: &Snapshot<T>) {
+    let _id = ui.push_id(id);
+
+    let mut only_changes = ONLY_CHANGES.with(|map| map.borrow().get(id).copied().unwrap_or(false));
+    if ui.checkbox("only changes", &mut only_changes) {
+        ONLY_CHANGES.with(|map| map.borrow_mut().insert(id.to_string(), only_changes));
+    }
+
+    let flags = TableFlags::RESIZABLE | TableFlags::BORDERS | TableFlags::ROW_BG;
+    let _table = match ui.begin_table_with_flags("##igri_compare", 3, flags) {
+        Some(table) => table,
+        None => return,
+    };
+
+    ui.table_setup_column("path");
+    ui.table_setup_column("before");
+    ui.table_setup_column("after");
+    ui.table_headers_row();
+
+    for (path, old, new) in self::merge(&before.entries, &after.entries) {
+        let changed = old != new;
+        if only_changes && !changed {
+            continue;
+        }
+
+        let _color = changed.then(|| ui.push_style_color(imgui::StyleColor::Text, CHANGED_COLOR));
+
+        ui.table_next_row();
+        for text in [Some(&path), old.as_ref(), new.as_ref()] {
+            ui.table_next_column();
+            match text {
+                Some(text) => ui.text(text),
+                None => ui.text_disabled("<none>"),
+            }
+        }
+    }
+}
+
+/// `(path, old, new)` for the union of the leaf paths, in the visit order
+fn merge(
+    old: &[(String, String)],
+    new: &[(String, String)],
+) -> Vec<(String, Option<String>, Option<String>)> {
+    let mut merged = Vec::<(String, Option<String>, Option<String>)>::new();
+    let mut indices = HashMap::new();
+
+    for (path, text) in old {
+        indices.insert(path.clone(), merged.len());
+        merged.push((path.clone(), Some(text.clone()), None));
+    }
+
+    for (path, text) in new {
+        match indices.get(path) {
+            Some(&i) => merged[i].2 = Some(text.clone()),
+            None => merged.push((path.clone(), None, Some(text.clone()))),
+        }
+    }
+
+    merged
+}
@@ -0,0 +1,242 @@
+/*!
+Search filter that prunes the inspection tree
+
+Set a [`Filter`] to the [`crate::InspectCtx`], and the standard inspectors ([`crate::tree_node`],
+[`crate::leaf`] and sequences) hide the branches without matches. A branch matches if its field
+name or label contains the query. With [`Filter::values`], leaf values are matched, too.
+
+The matches are found once per root inspection by [`crate::Inspect::visit`]. Values without `visit`
+are shown, as their contents are unknown, and `#[inspect(with = "..")]` functions without the
+context are matched by their labels.
+*/
+
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, HashMap},
+};
+
+use imgui::Ui;
+
+use crate::{
+    path::{self, FieldPath, Segment},
+    persist,
+    visit::{Leaf, Visitor},
+    Inspect,
+};
+
+/// Color of the matched items
+pub const HIGHLIGHT_COLOR: [f32; 4] = [1.0, 0.85, 0.3, 1.0];
+
+thread_local! {
+    /// Window title → filter of [`crate::window`]s
+    static FILTERS: RefCell<HashMap<String, Filter>> = RefCell::new(HashMap::new());
+}
+
+pub(crate) fn load(key: &str) -> Filter {
+    FILTERS.with(|map| map.borrow().get(key).cloned().unwrap_or_default())
+}
+
+pub(crate) fn save(key: &str, filter: &Filter) {
+    FILTERS.with(|map| map.borrow_mut().insert(key.to_string(), filter.clone()));
+    persist::on_change();
+}
+
+pub(crate) fn all() -> BTreeMap<String, Filter> {
+    FILTERS.with(|map| {
+        map.borrow()
+            .iter()
+            .map(|(key, filter)| (key.clone(), filter.clone()))
+            .collect()
+    })
+}
+
+/// Search filter of the inspection tree
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Filter {
+    /// Case-insensitive query
+    pub query: String,
+    /// Match stringified leaf values, too
+    pub values: bool,
+}
+
+impl Filter {
+    pub fn new(query: impl Into<String>) -> Self {
+        Self {
+            query: query.into(),
+            values: false,
+        }
+    }
+
+    /// If the filter is non-empty
+    pub fn is_active(&self) -> bool {
+        !self.query.trim().is_empty()
+    }
+
+    /// If the text contains the query, ignoring case
+    pub fn matches(&self, text: &str) -> bool {
+        let query = self.query.trim().to_lowercase();
+        !query.is_empty() && text.to_lowercase().contains(&query)
+    }
+
+    /// Draws the filter input box. Returns `true` if the filter is changed
+    pub fn draw(&mut self, ui: &Ui) -> bool {
+        let mut changed = ui
+            .input_text("filter", &mut self.query)
+            .hint("field name or label")
+            .build();
+        ui.same_line();
+        changed |= ui.checkbox("values", &mut self.values);
+        changed
+    }
+
+    /// If any field name, label or value (with [`Filter::values`]) in the value matches
+    pub fn matches_within(&self, x: &mut dyn Inspect) -> bool {
+        let mut visitor = Match {
+            filter: self,
+            found: false,
+        };
+        x.visit(&mut visitor);
+        visitor.found
+    }
+
+    /// Finds matches within the value at the path and each of its visited descendants
+    pub(crate) fn find_within(
+        &self,
+        base: &str,
+        x: &mut dyn Inspect,
+        out: &mut HashMap<String, Within>,
+    ) {
+        let mut visitor = FindWithin {
+            filter: self,
+            base,
+            path: FieldPath::default(),
+            frames: vec![Frame::default()],
+            out,
+        };
+        x.visit(&mut visitor);
+        visitor.exit();
+    }
+}
+
+/// Matches within a value under the filter
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Within {
+    Match,
+    NoMatch,
+    /// Nothing was visited, e.g. the type does not implement [`Inspect::visit`]
+    Unknown,
+}
+
+/// Visibility of an item under the filter
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Visibility {
+    /// No filter, or in a matched branch
+    Shown,
+    /// The item matches the filter. Its whole branch is shown
+    Matched,
+    /// Some of the descendants match the filter. The node is opened automatically
+    Ancestor,
+    /// No match in the branch
+    Hidden,
+}
+
+/// Finds a match within the visited value
+struct Match<'a> {
+    filter: &'a Filter,
+    found: bool,
+}
+
+impl<'a> Visitor for Match<'a> {
+    fn enter(&mut self, segment: Segment) -> bool {
+        if let Segment::Field(name) = segment {
+            self.found |= self.filter.matches(name);
+        }
+        !self.found
+    }
+
+    fn exit(&mut self) {}
+
+    fn label(&mut self, label: &str) {
+        self.found |= self.filter.matches(label);
+    }
+
+    fn leaf(&mut self, leaf: &mut dyn Leaf) {
+        if self.filter.values {
+            self.found |= self.filter.matches(&leaf.to_text());
+        }
+    }
+}
+
+#[derive(Default)]
+struct Frame {
+    /// The value or its descendants match
+    within: bool,
+    /// The field name or the label matches
+    matched: bool,
+    visited: bool,
+}
+
+/// Finds matches within the visited value and each of its descendants
+struct FindWithin<'a> {
+    filter: &'a Filter,
+    base: &'a str,
+    /// Relative path
+    path: FieldPath,
+    frames: Vec<Frame>,
+    out: &'a mut HashMap<String, Within>,
+}
+
+impl<'a> Visitor for FindWithin<'a> {
+    fn enter(&mut self, segment: Segment) -> bool {
+        if let Some(parent) = self.frames.last_mut() {
+            parent.visited = true;
+        }
+
+        let matched = match segment {
+            Segment::Field(name) => self.filter.matches(name),
+            Segment::Index(_) => false,
+        };
+
+        self.path.push(segment);
+        self.frames.push(Frame {
+            matched,
+            ..Frame::default()
+        });
+        true
+    }
+
+    fn exit(&mut self) {
+        let frame = match self.frames.pop() {
+            Some(frame) => frame,
+            None => return,
+        };
+
+        let within = if frame.within {
+            Within::Match
+        } else if frame.visited {
+            Within::NoMatch
+        } else {
+            Within::Unknown
+        };
+        self.out
+            .insert(path::join(self.base, self.path.as_str()), within);
+        self.path.pop();
+
+        if let Some(parent) = self.frames.last_mut() {
+            parent.within |= frame.within || frame.matched;
+        }
+    }
+
+    fn label(&mut self, label: &str) {
+        if let Some(frame) = self.frames.last_mut() {
+            frame.matched |= self.filter.matches(label);
+        }
+    }
+
+    fn leaf(&mut self, leaf: &mut dyn Leaf) {
+        if let Some(frame) = self.frames.last_mut() {
+            frame.visited = true;
+            frame.within |= self.filter.values && self.filter.matches(&leaf.to_text());
+        }
+    }
+}
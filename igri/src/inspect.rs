@@ -1,14 +1,240 @@
-use imgui::Ui;
+use std::{
+    any::Any,
+    cell::{Cell, RefCell},
+    collections::{hash_map::DefaultHasher, HashMap},
+    fmt::{self, Write},
+    hash::{Hash, Hasher},
+};
+
+use imgui::{TreeNodeToken, Ui};
+
+use crate::{
+    diff,
+    filter::{self, Visibility},
+    journal,
+    menu::{self, MenuTarget},
+    multi, persist,
+    visit::{Leaf, Visitor},
+    watch, watchpoint, InspectCtx,
+};
 
 /// ImGUI runtime inspection
+///
+/// `Inspect` is object safe: `Box<dyn Inspect>` and `&mut dyn Inspect` are inspected with the type
+/// names of the values in their labels (e.g. `0: Enemy`). See also [`DynInspect`].
+///
+/// Implementors must override [`Inspect::inspect_ctx`] or [`Inspect::inspect`]. Their default
+/// implementations call each other; if neither is overridden, the value is shown as
+/// `<Inspect not implemented>`.
 pub trait Inspect {
     // mutable reference only
-    fn inspect(&mut self, ui: &Ui, label: &str);
+    /// Inspects the value in the context of an ongoing inspection. Default: calls
+    /// [`Inspect::inspect`], which continues the inspection with the context
+    fn inspect_ctx(&mut self, ctx: &mut InspectCtx, ui: &Ui, label: &str) {
+        let key = self::default_key(self);
+        let outer = DEFAULT_INSPECT_CTX.with(|d| d.replace(Some(key)));
+        self::with_ambient_ctx(ctx, || self.inspect(ui, label));
+        DEFAULT_INSPECT_CTX.with(|d| d.set(outer));
+    }
+
+    /// Inspects the value as a root with a default [`InspectCtx`], or in the ongoing inspection if
+    /// called from context-less code (old `inspect` impls and `with` functions, [`nest`], ..)
+    fn inspect(&mut self, ui: &Ui, label: &str) {
+        // called back from the default `inspect_ctx` of the same value: neither is overridden
+        if DEFAULT_INSPECT_CTX.with(|d| d.get()) == Some(self::default_key(self)) {
+            ui.label_text(label, "<Inspect not implemented>");
+            return;
+        }
+        self::ambient_ctx(|ctx| self.inspect_ctx(ctx, ui, label));
+    }
+
+    /// Visits the leaf values without drawing (see [`crate::visit`]). Default: no leaves
+    fn visit(&mut self, _visitor: &mut dyn Visitor) {}
+
+    /// Stable ImGUI ID of the value as a sequence element (see [`hash_id`]). Default: `None`, the
+    /// index is used
+    fn inspect_id(&self) -> Option<u64> {
+        None
+    }
+
+    /// Type name shown for trait objects. Default: [`std::any::type_name`]; derived as the type
+    /// name without the module path
+    fn inspect_type_name(&self) -> &'static str {
+        std::any::type_name::<Self>()
+    }
+}
+
+/// [`Inspect`] trait objects that can be downcast to their concrete types
+///
+/// Implemented for all the `'static` inspectable types. `Vec<Box<dyn DynInspect>>` holds
+/// heterogeneous objects (e.g. plugins) without a central enum. Downcast with the methods of
+/// `dyn DynInspect`, e.g. `plugins[0].downcast_ref::<Enemy>()`, which see through the `Box`.
+pub trait DynInspect: Inspect + Any + private::AsAny {}
+
+impl<T: Inspect + Any> DynInspect for T {}
+
+mod private {
+    use std::any::Any;
+
+    /// Sealed, so that `as_any` is never called on a `Box<dyn DynInspect>` itself (which is also a
+    /// `DynInspect`) instead of the boxed value
+    pub trait AsAny {
+        fn any_ref(&self) -> &dyn Any;
+        fn any_mut(&mut self) -> &mut dyn Any;
+    }
+
+    impl<T: Any> AsAny for T {
+        fn any_ref(&self) -> &dyn Any {
+            self
+        }
+
+        fn any_mut(&mut self) -> &mut dyn Any {
+            self
+        }
+    }
+}
+
+impl dyn DynInspect {
+    pub fn as_any(&self) -> &dyn Any {
+        self.any_ref()
+    }
+
+    pub fn as_any_mut(&mut self) -> &mut dyn Any {
+        self.any_mut()
+    }
+
+    pub fn downcast_ref<T: Any>(&self) -> Option<&T> {
+        self.as_any().downcast_ref()
+    }
+
+    pub fn downcast_mut<T: Any>(&mut self) -> Option<&mut T> {
+        self.as_any_mut().downcast_mut()
+    }
+}
+
+/// If values of the type are only known at runtime: trait objects (`dyn Inspect`,
+/// `dyn DynInspect`, `dyn Any`, ..). Pointers to them carry metadata (a vtable), so they're wider than
+/// pointers to sized types
+pub(crate) fn is_unsized<T: ?Sized>() -> bool {
+    std::mem::size_of::<*const T>() != std::mem::size_of::<*const ()>()
+}
+
+thread_local! {
+    /// Context of the ongoing inspection while context-less code runs
+    static AMBIENT_CTX: RefCell<Option<InspectCtx>> = const { RefCell::new(None) };
+    /// Value in the default [`Inspect::inspect_ctx`], to stop the defaults from calling each other
+    static DEFAULT_INSPECT_CTX: Cell<Option<DefaultKey>> = const { Cell::new(None) };
+}
+
+/// Address and type name of a value. The type tells apart a value from its first field
+type DefaultKey = (*const (), &'static str);
+
+fn default_key<T: ?Sized>(x: &T) -> DefaultKey {
+    (x as *const T as *const (), std::any::type_name::<T>())
+}
+
+/// Runs context-less code with the context as the ambient context, so that [`Inspect::inspect`]
+/// calls in it continue the inspection
+pub(crate) fn with_ambient_ctx<R>(ctx: &mut InspectCtx, f: impl FnOnce() -> R) -> R {
+    let outer = AMBIENT_CTX.with(|a| a.replace(Some(std::mem::take(ctx))));
+    let ret = f();
+    *ctx = AMBIENT_CTX.with(|a| a.replace(outer)).unwrap_or_default();
+    ret
+}
+
+/// Runs the closure with the ambient context, or a new one if no inspection is in progress
+fn ambient_ctx<R>(f: impl FnOnce(&mut InspectCtx) -> R) -> R {
+    match AMBIENT_CTX.with(|a| a.borrow_mut().take()) {
+        Some(mut ctx) => {
+            let ret = f(&mut ctx);
+            AMBIENT_CTX.with(|a| *a.borrow_mut() = Some(ctx));
+            ret
+        }
+        None => f(&mut InspectCtx::new()),
+    }
+}
+
+/// Standard method to inspect a trait object, labelled with the type name of the value
+pub fn inspect_dyn<T: Inspect + ?Sized>(x: &mut T, ctx: &mut InspectCtx, ui: &Ui, label: &str) {
+    // the path is by the label, not the type name
+    ctx.root(label, |ctx| {
+        let label = format!("{}: {}", label, x.inspect_type_name());
+        x.inspect_ctx(ctx, ui, &label);
+    });
+}
+
+/// Hashes the value into an ID for [`Inspect::inspect_id`]
+pub fn hash_id<T: Hash + ?Sized>(x: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    x.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Function for `#[inspect(with = "..")]`: `fn(&mut T, &mut InspectCtx, &Ui, &str)`, or the old
+/// `fn(&mut T, &Ui, &str)` without the context
+///
+/// With the search filter, functions without the context are hidden unless the label or the field
+/// name matches. Functions with the context are responsible for filtering their contents (e.g. with
+/// [`InspectCtx::is_label_hidden`]).
+pub trait InspectWith<T: ?Sized, Marker> {
+    fn call(self, x: &mut T, ctx: &mut InspectCtx, ui: &Ui, label: &str);
+
+    /// If the value is hidden by the filter. Default: `false`
+    fn is_hidden(&self, _ctx: &mut InspectCtx, _label: &str) -> bool {
+        false
+    }
+}
+
+/// [`InspectWith`] marker of `fn(&mut T, &mut InspectCtx, &Ui, &str)`
+pub enum WithCtx {}
+
+/// [`InspectWith`] marker of `fn(&mut T, &Ui, &str)`
+pub enum WithoutCtx {}
+
+impl<T: ?Sized, F: FnOnce(&mut T, &mut InspectCtx, &Ui, &str)> InspectWith<T, WithCtx> for F {
+    fn call(self, x: &mut T, ctx: &mut InspectCtx, ui: &Ui, label: &str) {
+        self(x, ctx, ui, label)
+    }
+}
+
+impl<T: ?Sized, F: FnOnce(&mut T, &Ui, &str)> InspectWith<T, WithoutCtx> for F {
+    fn call(self, x: &mut T, ctx: &mut InspectCtx, ui: &Ui, label: &str) {
+        self::with_ambient_ctx(ctx, || self(x, ui, label))
+    }
+
+    fn is_hidden(&self, ctx: &mut InspectCtx, label: &str) -> bool {
+        ctx.is_label_hidden(label)
+    }
+}
+
+/// Standard method to call `#[inspect(with = "..")]` functions
+pub fn inspect_with<T: ?Sized, M>(
+    f: impl InspectWith<T, M>,
+    x: &mut T,
+    ctx: &mut InspectCtx,
+    ui: &Ui,
+    label: &str,
+) {
+    ctx.root(label, |ctx| {
+        if !f.is_hidden(ctx, label) {
+            f.call(x, ctx, ui, label);
+        }
+    });
+}
+
+/// If the `#[inspect(with = "..")]` field at the current path is hidden by the filter
+pub fn is_with_hidden<T: ?Sized, M>(
+    f: &impl InspectWith<T, M>,
+    _x: &T,
+    ctx: &mut InspectCtx,
+    label: &str,
+) -> bool {
+    f.is_hidden(ctx, label)
 }
 
 /// Standard method to inspect a sequence of inspectable items
 pub fn seq<'a, T: Inspect + 'a>(xs: impl Iterator<Item = &'a mut T>, ui: &Ui, label: &str) {
-    self::seq_indexed(xs.enumerate(), ui, label)
+    self::ambient_ctx(|ctx| self::seq_ctx(xs, ctx, ui, label))
 }
 
 /// Standard method to inspect a sequence of indexed, inspectable items
@@ -17,23 +243,418 @@ pub fn seq_indexed<'a, T: Inspect + 'a>(
     ui: &Ui,
     label: &str,
 ) {
-    self::nest(ui, label, || {
-        use std::fmt::Write;
-        let mut buf = String::with_capacity(2);
+    self::ambient_ctx(|ctx| self::seq_indexed_ctx(xs_indexed, ctx, ui, label))
+}
+
+/// [`seq`] in the context of an ongoing inspection
+pub fn seq_ctx<'a, T: Inspect + 'a>(
+    xs: impl Iterator<Item = &'a mut T>,
+    ctx: &mut InspectCtx,
+    ui: &Ui,
+    label: &str,
+) {
+    self::seq_indexed_ctx(xs.enumerate(), ctx, ui, label)
+}
+
+/// [`seq_indexed`] in the context of an ongoing inspection
+pub fn seq_indexed_ctx<'a, T: Inspect + 'a>(
+    xs_indexed: impl Iterator<Item = (usize, &'a mut T)>,
+    ctx: &mut InspectCtx,
+    ui: &Ui,
+    label: &str,
+) {
+    self::nest_ctx(ctx, ui, label, |ctx| self::seq_items(xs_indexed, ctx, ui));
+}
+
+/// Inspects the indexed items, labelled with the indices
+pub(crate) fn seq_items<'a, T: Inspect + 'a>(
+    xs_indexed: impl Iterator<Item = (usize, &'a mut T)>,
+    ctx: &mut InspectCtx,
+    ui: &Ui,
+) {
+    self::seq_items_by(
+        xs_indexed,
+        ctx,
+        ui,
+        |buf, i, _x| write!(buf, "{}", i),
+        // keyed elements keep the UI state when the sequence is reordered
+        |i, x| x.inspect_id().unwrap_or(i as u64),
+        |_i| {},
+    );
+}
+
+/// Inspects the indexed items with the labels and IDs from the closures. `prefix` draws widgets
+/// before each shown item
+pub(crate) fn seq_items_by<'a, T: Inspect + 'a>(
+    xs_indexed: impl Iterator<Item = (usize, &'a mut T)>,
+    ctx: &mut InspectCtx,
+    ui: &Ui,
+    mut label: impl FnMut(&mut String, usize, &T) -> fmt::Result,
+    mut id: impl FnMut(usize, &T) -> u64,
+    mut prefix: impl FnMut(usize),
+) {
+    let mut buf = String::with_capacity(2);
+    let mut n_hidden = 0;
 
-        for (i, x) in xs_indexed {
-            buf.clear();
-            write!(buf, "{}", i).unwrap();
+    for (i, x) in xs_indexed {
+        buf.clear();
+        label(&mut buf, i, x).unwrap();
 
-            x.inspect(ui, &buf);
+        ctx.index(i, |ctx| {
+            let _id = ui.push_id(self::fold_id(id(i, x)));
+
+            if ctx.is_hidden(&buf, x) {
+                n_hidden += 1;
+            } else {
+                prefix(i);
+                x.inspect_ctx(ctx, ui, &buf);
+            }
+        });
+    }
+
+    if n_hidden > 0 {
+        ui.text_disabled(format!("({} hidden by the filter)", n_hidden));
+    }
+}
+
+/// Standard method to inspect a sequence keyed by the elements, e.g. by entity IDs or names
+///
+/// The elements are labelled and scoped in the ID stack by the keys, with the indices as secondary
+/// info, so that their UI state stays with them when the sequence is reordered. Duplicate keys are
+/// reported below the elements.
+pub fn seq_keyed<S, T, K>(
+    xs: &mut S,
+    ctx: &mut InspectCtx,
+    ui: &Ui,
+    label: &str,
+    key: impl Fn(&T) -> &K,
+) where
+    S: Inspect,
+    for<'a> &'a mut S: IntoIterator<Item = &'a mut T>,
+    T: Inspect,
+    K: fmt::Display + Hash + ?Sized,
+{
+    ctx.root(label, |ctx| {
+        if let Some(_token) = self::tree_node(ctx, ui, label, false, Some(MenuTarget::new(xs))) {
+            let (ids, n_duplicates) = self::keyed_ids(xs.into_iter().map(|x| key(x)));
+
+            self::seq_items_by(
+                xs.into_iter().enumerate(),
+                ctx,
+                ui,
+                |buf, i, x| self::write_keyed_label(buf, key(x), i),
+                |i, _x| ids[i],
+                |_i| {},
+            );
+
+            if n_duplicates > 0 {
+                ui.text_colored(
+                    crate::validate::ERROR_COLOR,
+                    format!("({} duplicate keys)", n_duplicates),
+                );
+            }
         }
     });
 }
 
+/// Writes the label of a keyed sequence element: the key and the index
+///
+/// `##` in the key is split so that ImGUI doesn't hide the rest of the key. The ID is given
+/// separately (see [`keyed_ids`]), so the label ends with a fixed `###` ID.
+pub fn write_keyed_label<K: fmt::Display + ?Sized>(
+    buf: &mut String,
+    key: &K,
+    i: usize,
+) -> fmt::Result {
+    let mut key = key.to_string();
+    while key.contains("##") {
+        key = key.replace("##", "# #");
+    }
+    write!(buf, "{} [{}]###key", key, i)
+}
+
+/// IDs of keyed sequence elements by the hashes of the keys, and the number of duplicate keys
+///
+/// Duplicate keys get distinct IDs by their occurrences, so that their widgets don't edit each
+/// other.
+pub fn keyed_ids<'a, K: Hash + ?Sized + 'a>(
+    keys: impl Iterator<Item = &'a K>,
+) -> (Vec<u64>, usize) {
+    let mut occurrences = HashMap::<u64, u64>::new();
+    let mut n_duplicates = 0;
+
+    let ids = keys
+        .map(|key| {
+            let id = self::hash_id(key);
+            let n = occurrences.entry(id).or_insert(0);
+            *n += 1;
+            if *n == 1 {
+                id
+            } else {
+                n_duplicates += 1;
+                self::hash_id(&(id, *n))
+            }
+        })
+        .collect();
+
+    (ids, n_duplicates)
+}
+
+/// Folds the ID into ImGUI's `int` ID
+fn fold_id(id: u64) -> i32 {
+    (id ^ (id >> 32)) as i32
+}
+
+/// Standard method to scope a field: pushes the field name to the path and to the ImGUI ID stack,
+/// so that the widgets of sibling fields don't share IDs
+pub fn field<R>(
+    ctx: &mut InspectCtx,
+    ui: &Ui,
+    name: &str,
+    f: impl FnOnce(&mut InspectCtx) -> R,
+) -> R {
+    let _id = ui.push_id(name);
+    ctx.field(name, f)
+}
+
+/// Standard method to scope a sequence element: pushes the index to the path and to the ImGUI ID
+/// stack
+pub fn index<R>(
+    ctx: &mut InspectCtx,
+    ui: &Ui,
+    i: usize,
+    f: impl FnOnce(&mut InspectCtx) -> R,
+) -> R {
+    let _id = ui.push_id(self::fold_id(i as u64));
+    ctx.index(i, f)
+}
+
 /// Standard method to nest a tree node
 pub fn nest<R, F: FnOnce() -> R>(ui: &Ui, label: &str, closure: F) -> Option<R> {
-    imgui::TreeNode::new(label)
-        // .opened(true, imgui::Condition::FirstUseEver)
-        .flags(imgui::TreeNodeFlags::OPEN_ON_ARROW | imgui::TreeNodeFlags::OPEN_ON_DOUBLE_CLICK)
-        .build(ui, closure)
+    self::ambient_ctx(|ctx| {
+        self::nest_ctx(ctx, ui, label, |ctx| self::with_ambient_ctx(ctx, closure))
+    })
+}
+
+/// [`nest`] in the context of an ongoing inspection
+pub fn nest_ctx<R, F: FnOnce(&mut InspectCtx) -> R>(
+    ctx: &mut InspectCtx,
+    ui: &Ui,
+    label: &str,
+    closure: F,
+) -> Option<R> {
+    ctx.root(label, |ctx| {
+        let _token = self::tree_node(ctx, ui, label, false, None)?;
+        Some(closure(ctx))
+    })
+}
+
+/// Standard method to push a tree node with the context menu on the header
+///
+/// Value actions of the menu (copy, paste and reset) are available if the target is given. With the
+/// search filter, the node is hidden (`None`) unless it has a match.
+pub fn tree_node<'ui>(
+    ctx: &mut InspectCtx,
+    ui: &Ui<'ui>,
+    label: &str,
+    default_open: bool,
+    mut target: Option<MenuTarget>,
+) -> Option<TreeNodeToken<'ui>> {
+    let visibility = ctx.visibility(label, target.as_mut().map(MenuTarget::value));
+    if visibility == Visibility::Hidden {
+        return None;
+    }
+
+    if let Some(target) = &mut target {
+        if ctx.depth() == 1 {
+            menu::capture_initial(ctx.path(), target.value());
+        }
+    }
+
+    let mut node = imgui::TreeNode::new(label)
+        .default_open(default_open)
+        .flags(imgui::TreeNodeFlags::OPEN_ON_ARROW | imgui::TreeNodeFlags::OPEN_ON_DOUBLE_CLICK);
+    if visibility == Visibility::Ancestor {
+        node = node.opened(true, imgui::Condition::Always);
+    } else if let Some(open) = persist::is_open(ctx.path()) {
+        node = node.opened(open, imgui::Condition::Once);
+    }
+
+    let changed = match &mut target {
+        Some(target) => diff::is_changed(ctx, target.value()),
+        None => false,
+    };
+
+    let token = {
+        let _color = self::text_color(ctx, visibility == Visibility::Matched, changed)
+            .map(|color| ui.push_style_color(imgui::StyleColor::Text, color));
+        node.push(ui)
+    };
+
+    // nodes opened by the filter are not the user's choice, and nodes are closed while their window
+    // skips drawing
+    if visibility != Visibility::Ancestor && ui.is_item_toggled_open() {
+        persist::set_open(ctx.path(), token.is_some(), default_open);
+    }
+
+    if let Some(target) = &mut target {
+        // leaves under closed nodes are not drawn, so check the watchpoints and sample the watched
+        // values under them, too
+        watchpoint::check_node(ctx.path(), target.value(), token.is_none());
+        if token.is_none() {
+            watch::sample(ctx.path(), target.value());
+        }
+    }
+
+    menu::item_menu(ctx, ui, target);
+    token
+}
+
+/// Standard method to inspect a leaf value: draws the widget with the context menu
+///
+/// The widget is disabled if the context is read-only. With the search filter, the widget is hidden
+/// unless it matches.
+pub fn leaf<T: Inspect + Leaf>(
+    ctx: &mut InspectCtx,
+    ui: &Ui,
+    label: &str,
+    x: &mut T,
+    draw: impl FnOnce(&mut T),
+) {
+    ctx.root(label, |ctx| {
+        let visibility = ctx.visibility(label, Some(x));
+        if visibility == Visibility::Hidden {
+            return;
+        }
+
+        if ctx.depth() == 1 {
+            menu::capture_initial(ctx.path(), x);
+        }
+
+        watchpoint::check_node(ctx.path(), x, false);
+
+        // edits through the widget are not outside changes, and are journaled
+        let before =
+            (watchpoint::covers(ctx.path()) || journal::is_recording()).then(|| x.to_text());
+
+        let old = diff::old_text(ctx, x);
+
+        {
+            let _disabled = ui.begin_disabled(ctx.is_read_only());
+            let highlight = matches!(visibility, Visibility::Matched | Visibility::Ancestor);
+            let _color = self::text_color(ctx, highlight, old.is_some())
+                .map(|color| ui.push_style_color(imgui::StyleColor::Text, color));
+            draw(x);
+        }
+
+        if let Some(before) = before {
+            let after = x.to_text();
+            if before != after {
+                watchpoint::sync(ctx.path(), x);
+                journal::record(ctx.path(), &[(String::new(), after)]);
+            }
+        }
+
+        menu::item_menu(ctx, ui, Some(MenuTarget::new(x)));
+        watch::record(ctx.path(), x);
+
+        if let Some(old) = old {
+            if ui.is_item_hovered() {
+                ui.tooltip_text(format!("was: {}", old));
+            }
+        }
+
+        if multi::is_mixed(ctx) {
+            ui.same_line();
+            ui.text_disabled("(mixed)");
+        }
+    });
+}
+
+/// Flash color of changed watchpoints, the color of changes since the diff checkpoint, or the
+/// highlight color of the filter matches
+fn text_color(ctx: &InspectCtx, highlight: bool, changed: bool) -> Option<[f32; 4]> {
+    if watchpoint::is_flashing(ctx.path()) {
+        Some(watchpoint::FLASH_COLOR)
+    } else if changed {
+        Some(diff::CHANGED_COLOR)
+    } else if highlight {
+        Some(filter::HIGHLIGHT_COLOR)
+    } else {
+        None
+    }
+}
+
+/// Standard method to show a top-level inspector window with the search filter box
+///
+/// The filter is remembered per window title.
+pub fn window(ui: &Ui, title: &str, f: impl FnOnce(&mut InspectCtx)) {
+    imgui::Window::new(title)
+        .size([400.0, 600.0], imgui::Condition::FirstUseEver)
+        .build(ui, || self::window_contents(ui, title, f));
+
+    persist::flush_debounced();
+}
+
+/// Contents of [`window`]: the search filter box of the window and the context with the filter
+pub(crate) fn window_contents(ui: &Ui, title: &str, f: impl FnOnce(&mut InspectCtx)) {
+    let mut filter = filter::load(title);
+    if filter.draw(ui) {
+        filter::save(title, &filter);
+    }
+    ui.separator();
+
+    let mut ctx = InspectCtx::new();
+    ctx.set_filter(Some(filter));
+    f(&mut ctx);
+}
+
+/// Standard method to show a tooltip on hovering the first line of the items
+pub fn tooltip<R, F: FnOnce() -> R>(ui: &Ui, text: &str, closure: F) -> R {
+    let top = ui.cursor_screen_pos()[1];
+
+    let group = ui.begin_group();
+    let ret = closure();
+    group.end();
+
+    // only the first line (label or tree node header), so that nested items don't show it, too
+    if ui.is_item_hovered() && ui.io().mouse_pos[1] < top + ui.frame_height() {
+        ui.tooltip_text(text);
+    }
+
+    ret
+}
+
+/// Standard method to show a button, optionally with a confirmation popup
+///
+/// Returns `true` when the action is clicked (and confirmed).
+pub fn button(ui: &Ui, label: &str, confirm: Option<&str>) -> bool {
+    let clicked = ui.button(label);
+
+    let msg = match confirm {
+        Some(msg) => msg,
+        None => return clicked,
+    };
+
+    let popup_id = format!("{}##confirm", label);
+    if clicked {
+        ui.open_popup(&popup_id);
+    }
+
+    let mut confirmed = false;
+    imgui::PopupModal::new(&popup_id)
+        .always_auto_resize(true)
+        .build(ui, || {
+            ui.text(msg);
+            if ui.button("OK") {
+                confirmed = true;
+                ui.close_current_popup();
+            }
+            ui.same_line();
+            if ui.button("Cancel") {
+                ui.close_current_popup();
+            }
+        });
+
+    confirmed
 }
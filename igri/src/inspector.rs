@@ -0,0 +1,202 @@
+/*!
+Registry of named inspection roots, shown in one window
+*/
+
+use std::{cell::RefCell, fmt, rc::Rc};
+
+use imgui::Ui;
+
+use crate::{inspect, persist, Inspect, InspectCtx};
+
+/// Gives the `&mut` access to the root to the inner closure
+type Access = Box<dyn FnMut(&mut dyn FnMut(&mut dyn Inspect))>;
+
+/// Named root of an [`Inspector`]
+struct Root {
+    name: String,
+    access: Access,
+    /// Shown in the inspector window
+    shown: bool,
+    /// Shown in its own window
+    detached: bool,
+}
+
+/// Window with named roots: a root list, a search box and a tree per root. Roots can be detached
+/// into their own windows
+///
+/// ```ignore
+/// let mut inspector = igri::Inspector::new("Inspector");
+/// inspector.register("settings", settings.clone());
+/// inspector.register_with("player", move |f| f(&mut world.borrow_mut().player));
+///
+/// // every frame
+/// inspector.draw(ui);
+/// ```
+pub struct Inspector {
+    title: String,
+    roots: Vec<Root>,
+}
+
+impl fmt::Debug for Inspector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Inspector")
+            .field("title", &self.title)
+            .field("roots", &self.names().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+impl Default for Inspector {
+    fn default() -> Self {
+        Self::new("Inspector")
+    }
+}
+
+impl Inspector {
+    pub fn new(title: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            roots: Vec::new(),
+        }
+    }
+
+    /// Registers the shared value as a root, replacing the root with the same name
+    pub fn register<T: Inspect + 'static>(
+        &mut self,
+        name: impl Into<String>,
+        x: Rc<RefCell<T>>,
+    ) -> &mut Self {
+        self.register_with(name, move |f: &mut dyn FnMut(&mut T)| {
+            // skipped while borrowed elsewhere
+            if let Ok(mut x) = x.try_borrow_mut() {
+                f(&mut *x);
+            }
+        })
+    }
+
+    /// Registers a closure giving the `&mut` access to the root, replacing the root with the same
+    /// name
+    pub fn register_with<T: Inspect + 'static>(
+        &mut self,
+        name: impl Into<String>,
+        mut access: impl FnMut(&mut dyn FnMut(&mut T)) + 'static,
+    ) -> &mut Self {
+        let access: Access = Box::new(move |f| access(&mut |x: &mut T| f(x)));
+        let name = name.into();
+
+        match self.roots.iter_mut().find(|root| root.name == name) {
+            Some(root) => root.access = access,
+            None => self.roots.push(Root {
+                name,
+                access,
+                shown: true,
+                detached: false,
+            }),
+        }
+
+        self
+    }
+
+    /// Returns `true` if the root was registered
+    pub fn unregister(&mut self, name: &str) -> bool {
+        let len = self.roots.len();
+        self.roots.retain(|root| root.name != name);
+        self.roots.len() != len
+    }
+
+    /// Names of the roots in the registration order
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.roots.iter().map(|root| root.name.as_str())
+    }
+
+    pub fn is_detached(&self, name: &str) -> bool {
+        self.roots
+            .iter()
+            .any(|root| root.name == name && root.detached)
+    }
+
+    /// Shows the root in its own window, or back in the inspector window
+    pub fn set_detached(&mut self, name: &str, detached: bool) {
+        if let Some(root) = self.roots.iter_mut().find(|root| root.name == name) {
+            root.detached = detached;
+        }
+    }
+
+    /// Shows the inspector window and the detached root windows
+    pub fn draw(&mut self, ui: &Ui) {
+        let title = &self.title;
+        let roots = &mut self.roots;
+
+        imgui::Window::new(title)
+            .size([400.0, 600.0], imgui::Condition::FirstUseEver)
+            .build(ui, || {
+                self::root_list(ui, roots);
+
+                inspect::window_contents(ui, title, |ctx| {
+                    for root in roots.iter_mut().filter(|root| root.shown && !root.detached) {
+                        self::inspect_root(root, ctx, ui);
+                    }
+                });
+            });
+
+        for root in roots.iter_mut().filter(|root| root.detached) {
+            let mut open = true;
+            // the filter is remembered per window title, as in `crate::window`
+            let window_title = format!("{}##igri_detached", root.name);
+
+            imgui::Window::new(&window_title)
+                .size([300.0, 400.0], imgui::Condition::FirstUseEver)
+                .opened(&mut open)
+                .build(ui, || {
+                    inspect::window_contents(ui, &window_title, |ctx| {
+                        self::inspect_root(root, ctx, ui)
+                    });
+                });
+
+            // closing the window attaches the root back
+            if !open {
+                root.detached = false;
+            }
+        }
+
+        persist::flush_debounced();
+    }
+}
+
+/// Draws the root list: visibility checkboxes and detach buttons
+fn root_list(ui: &Ui, roots: &mut [Root]) {
+    if !ui.collapsing_header("Roots", imgui::TreeNodeFlags::empty()) {
+        return;
+    }
+
+    if roots.is_empty() {
+        ui.text_disabled("Register roots with `Inspector::register`");
+    }
+
+    for root in roots.iter_mut() {
+        let _id = ui.push_id(root.name.as_str());
+
+        let label = if root.detached { "attach" } else { "detach" };
+        if ui.small_button(label) {
+            root.detached = !root.detached;
+        }
+        ui.same_line();
+
+        let _disabled = ui.begin_disabled(root.detached);
+        ui.checkbox(&root.name, &mut root.shown);
+    }
+}
+
+fn inspect_root(root: &mut Root, ctx: &mut InspectCtx, ui: &Ui) {
+    let name = &root.name;
+    let mut inspected = false;
+
+    (root.access)(&mut |x| {
+        inspected = true;
+        x.inspect_ctx(ctx, ui, name);
+    });
+
+    if !inspected {
+        ui.text_disabled(format!("{} (unavailable)", name));
+    }
+}
@@ -0,0 +1,192 @@
+/*!
+Journal of the edits made through the inspector: record, save and replay
+
+Start recording with [`start`]. Every edit through the inspector (widgets, context menu and
+multi-selection) is recorded as a timestamped field path and its new value. Save the [`Journal`] to a
+file, and replay it against a fresh instance of the root to reproduce the session, e.g. in a
+regression test:
+
+```ignore
+let journal = igri::journal::Journal::read("tuning.txt".as_ref())?;
+let mut world = World::default();
+journal.replay(&mut world, "world");
+assert_eq!(world.entities[0].hp, 120);
+```
+
+Edits made by `#[inspect(with = ..)]` functions are recorded only if they use the standard leaf
+inspectors.
+*/
+
+use std::{
+    cell::RefCell,
+    fs, io,
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use crate::{path, text, visit, Inspect};
+
+thread_local! {
+    /// Start time and the edits of the ongoing recording
+    static RECORDING: RefCell<Option<(Instant, Vec<Edit>)>> = const { RefCell::new(None) };
+}
+
+/// Edit of a leaf value
+#[derive(Debug, Clone, PartialEq)]
+pub struct Edit {
+    /// Time since the recording started
+    pub time: Duration,
+    /// Path of the leaf, starting with the root label
+    pub path: String,
+    /// New value as text (see [`crate::visit::Leaf`])
+    pub value: String,
+}
+
+/// Recorded edits
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Journal {
+    pub edits: Vec<Edit>,
+}
+
+/// Result of [`Journal::replay`]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Replay {
+    /// Number of the applied edits
+    pub applied: usize,
+    /// Edits under the root that could not be applied: the path does not exist in the value (e.g. an
+    /// element beyond the end of a sequence that was longer when recording) or the value is invalid
+    pub unapplied: Vec<Edit>,
+}
+
+impl Replay {
+    /// If all the edits under the root were applied
+    pub fn is_complete(&self) -> bool {
+        self.unapplied.is_empty()
+    }
+}
+
+/// Starts recording, discarding the ongoing recording
+pub fn start() {
+    RECORDING.with(|r| *r.borrow_mut() = Some((Instant::now(), Vec::new())));
+}
+
+/// Stops recording and returns the recorded edits
+pub fn stop() -> Journal {
+    let edits = RECORDING.with(|r| r.borrow_mut().take().map(|(_, edits)| edits));
+    Journal {
+        edits: edits.unwrap_or_default(),
+    }
+}
+
+pub fn is_recording() -> bool {
+    RECORDING.with(|r| r.borrow().is_some())
+}
+
+/// Number of the edits recorded so far
+pub fn len() -> usize {
+    RECORDING.with(|r| r.borrow().as_ref().map_or(0, |(_, edits)| edits.len()))
+}
+
+/// Records the leaf values under the base path if recording
+pub(crate) fn record(base: &str, entries: &[(String, String)]) {
+    RECORDING.with(|r| {
+        if let Some((start, edits)) = r.borrow_mut().as_mut() {
+            let time = start.elapsed();
+            edits.extend(entries.iter().map(|(rel, value)| Edit {
+                time,
+                path: path::join(base, rel),
+                value: value.clone(),
+            }));
+        }
+    });
+}
+
+/// Records all the leaf values of the value at the path if recording
+pub(crate) fn record_value(path: &str, x: &mut dyn Inspect) {
+    if self::is_recording() {
+        self::record(path, &visit::collect(x));
+    }
+}
+
+impl Journal {
+    /// Applies the edits to the root value labelled `root`. Edits of other roots are skipped
+    ///
+    /// Edits only set existing leaves; they don't grow sequences. Check [`Replay::unapplied`] when
+    /// the replayed value may not have the same shape as the recorded one.
+    pub fn replay<T: Inspect + ?Sized>(&self, x: &mut T, root: &str) -> Replay {
+        self.replay_until(x, root, Duration::MAX)
+    }
+
+    /// Applies the edits made until the time
+    pub fn replay_until<T: Inspect + ?Sized>(
+        &self,
+        x: &mut T,
+        root: &str,
+        time: Duration,
+    ) -> Replay {
+        let mut replay = Replay::default();
+
+        // one by one, as an edit can depend on the former ones (e.g. enum variant switch)
+        for edit in self.edits.iter().filter(|edit| edit.time <= time) {
+            let rel = match path::strip(root, &edit.path) {
+                Some(rel) => rel,
+                None => continue,
+            };
+
+            if visit::apply(x, &[(rel.to_string(), edit.value.clone())]) > 0 {
+                replay.applied += 1;
+            } else {
+                replay.unapplied.push(edit.clone());
+            }
+        }
+
+        replay
+    }
+
+    pub fn read(file: &Path) -> io::Result<Self> {
+        let text = fs::read_to_string(file)?;
+        Ok(Self::parse(&text))
+    }
+
+    pub fn write(&self, file: &Path) -> io::Result<()> {
+        fs::write(file, self.to_text())
+    }
+
+    /// Parses `<seconds>\t<path>\t<value>` lines, skipping invalid lines
+    pub fn parse(text: &str) -> Self {
+        let edits = text
+            .lines()
+            .filter_map(|line| {
+                let mut cols = line.splitn(3, '\t');
+                let time = cols.next()?.parse::<f64>().ok()?;
+                // negative, infinite or too large times are invalid
+                let time = Duration::try_from_secs_f64(time).ok()?;
+                let path = cols.next()?;
+                let value = cols.next()?;
+                Some(Edit {
+                    time,
+                    path: text::unescape(path),
+                    value: text::unescape(value),
+                })
+            })
+            .collect();
+
+        Self { edits }
+    }
+
+    /// `<seconds>\t<path>\t<value>` lines. Tabs, newlines and backslashes in the paths and values
+    /// are escaped
+    pub fn to_text(&self) -> String {
+        self.edits
+            .iter()
+            .map(|edit| {
+                format!(
+                    "{:.3}\t{}\t{}\n",
+                    edit.time.as_secs_f64(),
+                    text::escape(&edit.path),
+                    text::escape(&edit.value)
+                )
+            })
+            .collect()
+    }
+}
@@ -32,8 +32,385 @@ restrictive, you can override using `#[inspect(with = "<function>")]` atttribute
 | `with = "<function>"` | type or field | Override the inspection with the function                                             |
 | `as  = "<Type>"`      | type or field | Inspect as the type, converting with `Into`                                           |
 | `open = <bool>`       | type          | If the node is open by default                                                        |
-| `bounds = "<bounds>"` | type          | Override `where` boundary for the `Inspect` impl (default: each `FieldType: Inspect`) |
+| `bounds = "<bounds>"` | type          | Override `where` boundary for the `Inspect` impl (default: inferred, see below)       |
+| `bounds = "<bounds>"` | variant       | Override the inferred `where` boundary for the variant fields                         |
+| `bound = "<bounds>"`  | field         | Override the inferred `where` boundary for the field                                  |
 | `no_tag`              | type (`enum`) | Disable tag selector (see `enum` support for more info)                               |
+| `crate = "<path>"`    | type          | Path to `igri` (default: resolved from `Cargo.toml`, see below)                       |
+| `label = "<label>"`   | field         | Label of the field (default: field name or index)                                     |
+| `tooltip = "<text>"`  | field/variant | Tooltip on hovering the field or the enum tag                                         |
+| `doc_tooltips`        | type          | Show doc comments of fields and variants as tooltips                                  |
+| `group = "<group>"`   | field         | Group the field (see field layout)                                                    |
+| `layout = "<layout>"` | type          | Layout of the fields: `headers` (default), `tabs` or `columns` (see field layout)     |
+| `visible_if = "<expr>"` | field       | Show the field only if the expression is true (see conditional fields)                |
+| `enabled_if = "<expr>"` | field       | Disable the field unless the expression is true (see conditional fields)              |
+| `validate = "<path>"` | type or field | Show an error unless `fn(&T) -> Result<(), impl Display>` returns `Ok`               |
+| `min = <value>`       | field         | Clamp the field, showing an error on out-of-range edits                               |
+| `max = <value>`       | field         | Clamp the field, showing an error on out-of-range edits                               |
+| `button("<label>", "<method>")` | type | Button calling a `&mut self` method (see buttons)                              |
+| `id = "<expr>"`       | type          | Stable ImGUI ID of sequence elements from a hashable expression (see widget IDs)      |
+| `key = "<field>"`     | field         | Label and scope the sequence elements by their field (see widget IDs)                 |
+| `table`               | field         | Show the sequence as a table, a row per element (see table view)                      |
+| `multi_select`        | field         | Select sequence elements and edit them together (see multi-selection)                 |
+
+# `where` clause inference
+
+Like `serde`, `#[derive(Inspect)]` bounds only the type parameters that appear in the inspected
+field types (`T: Inspect`, plus `T: Default` for `enum`s with the tag selector). So recursive types
+such as `struct Node { children: Vec<Node> }` derive without manual `bounds`. Fields with `skip`,
+`with` or `as` are not bounded, except that skipped fields of `enum`s with the tag selector are
+bounded by `FieldType: Default`.
+
+# Field layout
+
+Fields with `#[inspect(group = "<group>")]` are grouped by the type-level `layout`, keeping the field
+order inside each group. Ungrouped fields come first. `in_place` structs with groups are indented
+so that their groups are not mixed with the parent's. `group` is not supported on `enum` variant
+fields.
+
+- `headers`: a collapsing header per group
+- `tabs`: a tab bar with a tab item per group
+- `columns`: a two-column label/value table, with a row per group name
+
+```ignore
+#[derive(Inspect)]
+#[inspect(layout = "tabs")]
+pub struct Entity {
+    name: String,
+    #[inspect(group = "Combat")]
+    hp: u32,
+    #[inspect(group = "Combat")]
+    atk: u32,
+    #[inspect(group = "Items")]
+    items: Vec<Item>,
+}
+```
+
+# Conditional fields
+
+`visible_if` and `enabled_if` take an expression evaluated every frame. In `struct`s, it can refer
+to `self`. In `enum` variants, it can refer to the sibling fields, which are bound by reference:
+
+```ignore
+#[derive(Inspect)]
+pub struct Light {
+    kind: LightKind,
+    #[inspect(visible_if = "self.kind == LightKind::Spot")]
+    falloff: f32,
+}
+
+#[derive(Inspect)]
+pub enum Shape {
+    Circle {
+        filled: bool,
+        #[inspect(enabled_if = "*filled")]
+        color: [f32; 3],
+    },
+}
+```
+
+# Validation
+
+Field-level `validate` shows the error beside the field. Type-level `validate` is for cross-field
+invariants and shows the error below the fields. `min` and `max` take a literal or an expression in a
+string (e.g. `min = "-1.0"`) and reject out-of-range edits by clamping. Out-of-range values set by
+the game are flagged, but left as they are:
+
+```ignore
+#[derive(Inspect)]
+#[inspect(validate = "Range::validate")]
+pub struct Range {
+    #[inspect(min = 0, max = 100)]
+    min: u32,
+    #[inspect(min = 0, max = 100)]
+    max: u32,
+}
+
+impl Range {
+    fn validate(&self) -> Result<(), &'static str> {
+        if self.min <= self.max { Ok(()) } else { Err("min > max") }
+    }
+}
+```
+
+# Buttons
+
+`button` can be repeated. Add `confirm` or `confirm = "<message>"` for destructive actions:
+
+```ignore
+#[derive(Inspect)]
+#[inspect(button("Respawn", "respawn"), button("Kill", "kill", confirm))]
+pub struct Entity {
+    hp: u32,
+}
+```
+
+# Inspection context
+
+`Inspect::inspect_ctx` takes an `InspectCtx` along with the `Ui`. It carries the current field path,
+the read-only mode and user data (keyed by type) through the inspection. `Inspect::inspect(ui,
+label)` starts an inspection with a default context:
+
+```ignore
+let mut ctx = InspectCtx::new();
+ctx.set_read_only(!editable);
+ctx.insert(MyConfig { .. });
+world.inspect_ctx(&mut ctx, ui, "world");
+```
+
+`#[inspect(with = "<function>")]` accepts `fn(&mut T, &mut InspectCtx, &Ui, &str)`, or
+`fn(&mut T, &Ui, &str)` without the context.
+
+Manual impls must implement either `inspect_ctx` or `inspect` (an impl with neither is shown as
+`<Inspect not implemented>`). Context-less code (`inspect` impls,
+`with` functions without the context, `igri::nest`, `igri::seq`) continues the ongoing inspection
+when it calls `Inspect::inspect`, so the nested values keep their paths. `igri::nest_ctx`,
+`igri::seq_ctx` and `igri::seq_indexed_ctx` take the context explicitly.
+
+# Search filter
+
+`igri::window(ui, title, |ctx| ..)` shows an inspector window with a filter box. The filter matches
+field names, labels and optionally leaf values (`values` checkbox). Branches without matches are
+hidden, branches with matches are opened, and the matches are highlighted:
+
+```ignore
+igri::window(ui, "Runtime inspector", |ctx| {
+    entities.inspect_ctx(ctx, ui, "entities");
+});
+```
+
+You can also set an `igri::filter::Filter` to your `InspectCtx` with `set_filter`.
+
+# Context menu
+
+Right-click on a field to open the context menu:
+
+- `Copy value` / `Paste value`: copy the value as text, and paste it into a field of the same type
+- `Reset to default` / `Reset to initial`: reset to `Default` or to the value on the first inspection
+- `Watch`: pin the field to the watch window (`igri::watch::window(ui)`), which keeps showing the
+  value and plots its recent history even if the source node is collapsed
+- `Watchpoint` / `Break on change`: log changes of the field made outside the inspector, flash the
+  node, and optionally call the break hook (`igri::watchpoint`)
+- `Copy path`: copy the field path such as `entities[1].hp`
+
+Pinned paths are kept in memory. Persist them over restarts with `igri::persist` below.
+
+# Persistence
+
+Call `igri::persist::set_file(Some("igri_state.txt"))` at startup to save the expanded tree nodes,
+pinned paths and window filters to a small file, and restore them on the next run. The state is
+keyed by field paths, so it survives reordering fields or windows. Changes are written by the
+inspector windows at most once a second; call `igri::persist::flush()` before exiting.
+
+Watchpoint changes are kept in memory and shown by `igri::watchpoint::window(ui)` by default. Set
+hooks to log them elsewhere, or to pause your game on `Break on change`:
+
+```ignore
+igri::watchpoint::set_log_hook(|change| log::info!("{:?}", change));
+igri::watchpoint::set_break_hook(|_path| PAUSED.with(|p| p.set(true)));
+```
+
+Copy, paste and reset work on the leaf values visited by `Inspect::visit`, which is derived. Manual
+`Inspect` impls can use `igri::leaf` for leaf values and `igri::tree_node` for nodes, and implement
+`visit` with `igri::visit::visit_field`:
+
+```ignore
+impl Inspect for Vec2 {
+    fn inspect_ctx(&mut self, ctx: &mut InspectCtx, ui: &Ui, label: &str) {
+        ctx.root(label, |ctx| {
+            if let Some(_token) = igri::tree_node(ctx, ui, label, false, Some(MenuTarget::new(self))) {
+                igri::field(ctx, ui, "x", |ctx| self.x.inspect_ctx(ctx, ui, "x"));
+                igri::field(ctx, ui, "y", |ctx| self.y.inspect_ctx(ctx, ui, "y"));
+            }
+        });
+    }
+
+    fn visit(&mut self, visitor: &mut dyn Visitor) {
+        igri::visit::visit_field(visitor, "x", &mut self.x);
+        igri::visit::visit_field(visitor, "y", &mut self.y);
+    }
+}
+```
+
+# Table view
+
+`#[inspect(table)]` on a sequence field of structs shows a table with a row per element and a column
+per field. Cells are editable, columns are resizable and sortable (the view is sorted, not the
+sequence), and rows can be filtered by their values:
+
+```ignore
+#[derive(Inspect)]
+pub struct World {
+    #[inspect(table)]
+    entities: Vec<Entity>,
+}
+```
+
+The columns come from `igri::table::Columns`, which is derived for structs. Fields with `with` or
+`as` are not shown in the table; other field attributes such as `min`, `max`, `validate` and
+`visible_if` apply to the cells as well. `table` can't be combined with `key` or `multi_select`.
+
+# Multi-selection
+
+`#[inspect(multi_select)]` on a sequence field adds a checkbox to each element. With two or more
+elements checked, the selection is inspected on top: an edit is applied to every selected element,
+and fields whose values differ among them are marked `(mixed)`. It works on the leaf values of the
+derived `Inspect::visit`, so any derived type supports it. The selection is kept by index, so it
+refers to different elements after the sequence is reordered:
+
+```ignore
+#[derive(Inspect)]
+pub struct World {
+    #[inspect(multi_select)]
+    entities: Vec<Entity>,
+}
+```
+
+# Snapshot diff
+
+`igri::diff::Snapshot::take(&mut x)` takes a checkpoint of the leaf values. Inspect the live value
+with `igri::diff::inspect_diff` to highlight the fields changed since the checkpoint, with the old
+values as tooltips. `igri::diff::compare` shows two snapshots of the same type side by side:
+
+```ignore
+let checkpoint = igri::diff::Snapshot::take(&mut world);
+world.tick();
+
+igri::window(ui, "What changed", |ctx| {
+    igri::diff::inspect_diff(&mut world, &checkpoint, ctx, ui, "world");
+});
+```
+
+# Edit journal
+
+`igri::journal::start` records every edit made through the inspector as a timestamped leaf path and
+its new value. Save the journal to a file and replay it against a fresh instance of the root, e.g. to
+turn a tuning session into a regression test:
+
+```ignore
+igri::journal::start();
+// .. edit the world in the inspector
+igri::journal::stop().write("tuning.txt".as_ref())?;
+
+let mut world = World::default();
+let replay = igri::journal::Journal::read("tuning.txt".as_ref())?.replay(&mut world, "world");
+assert!(replay.is_complete(), "unapplied edits: {:?}", replay.unapplied);
+```
+
+Edits set existing leaves only, so edits of sequence elements the fresh instance doesn't have are
+reported in `Replay::unapplied`.
+
+# Ad-hoc inspection
+
+`igri::inspect!` inspects local expressions labelled with their source text, without a label string
+or a window wrapper:
+
+```ignore
+igri::inspect!(ui, player.pos, speed, state);
+// in a window of its own
+igri::inspect!(ui, window = "Locals", player.pos, speed);
+```
+
+# Trait objects
+
+`Inspect` is object safe. `Box<dyn Inspect>` and `&mut dyn Inspect` are labelled with the type names
+of the values (`Inspect::inspect_type_name`, derived as the type name), so `Vec<Box<dyn Inspect>>`
+shows each element as `0: Enemy`. `Box<dyn igri::DynInspect>` can also be downcast to the concrete
+type (`as_any`, `downcast_ref` and `downcast_mut` refer to the boxed value), for heterogeneous
+objects such as plugins:
+
+```ignore
+let mut plugins: Vec<Box<dyn igri::DynInspect>> = vec![Box::new(Enemy::default())];
+plugins.inspect(ui, "plugins");
+let enemy = plugins[0].downcast_mut::<Enemy>();
+```
+
+`Box<dyn Any>` and `&mut dyn Any` are inspected by the inspectors of the concrete types registered
+with `igri::registry::register`, labelled with the type names. Values of unregistered types are
+labelled `<unregistered TypeName>`. `dyn Any` has no type name, so it is known only for the types
+registered before or boxed with `igri::registry::boxed`, and other values are labelled
+`<unregistered>`:
+
+```ignore
+igri::registry::register::<Enemy>();
+
+#[derive(Inspect)]
+pub struct Scene {
+    objects: Vec<Box<dyn Any>>,
+}
+```
+
+# Inspector registry
+
+`igri::Inspector` holds named roots and shows them in one window with a root list, a search box and
+a tree per root. A root can be detached into its own window:
+
+```ignore
+let mut inspector = igri::Inspector::new("Inspector");
+inspector.register("settings", settings.clone()); // `Rc<RefCell<Settings>>`
+inspector.register_with("player", move |f| f(&mut world.borrow_mut().player));
+
+// every frame
+inspector.draw(ui);
+```
+
+# Tweakable constants
+
+`igri::tweak!` registers a literal by its source location and returns its current value, so magic
+numbers outside any struct can be tweaked at runtime. `igri::tweak::window` shows them grouped by
+source file:
+
+```ignore
+let jump = igri::tweak!(4.5) * dt;
+igri::tweak::window(ui);
+```
+
+Persist the tweaked values to a sidecar file with `igri::tweak::set_file`, or write them back into
+the source with `igri::tweak::write_source`.
+
+# Widget IDs
+
+Every field is scoped in the ImGUI ID stack by its field name, and every sequence element by its
+index, so widgets with the same label (e.g. the `tag` of two `Option`s or the fields of two
+`in_place` structs) don't edit each other. Manual `Inspect` impls should scope their fields with
+`igri::field(ctx, ui, name, ..)` and `igri::index(ctx, ui, i, ..)`.
+
+Sequence elements with `#[inspect(id = "<expr>")]` are scoped by the hash of the expression instead
+of the index, so their UI state stays with them when the sequence is reordered:
+
+```ignore
+#[derive(Inspect)]
+#[inspect(id = "self.id")]
+pub struct Entity {
+    id: u32,
+    hp: u32,
+}
+```
+
+`#[inspect(key = "<field>")]` on a sequence field does the same with a field of the elements, and
+labels the elements with the key, showing the index as secondary info. Keys should be unique;
+duplicates are reported below the elements:
+
+```ignore
+#[derive(Inspect)]
+pub struct World {
+    #[inspect(key = "name")]
+    entities: Vec<Entity>,
+}
+```
+
+# Re-exported `igri`
+
+The derived code refers to `igri` by the dependency name in your `Cargo.toml`. If your crate uses
+`igri` only through a re-export (e.g. `engine::igri`), specify the path explicitly:
+
+```ignore
+#[derive(Inspect)]
+#[inspect(crate = "engine::igri")]
+pub struct Player {
+    hp: u32,
+}
+```
 
 # `dummy` feature
 
@@ -64,5 +441,130 @@ pub use inspect::*;
 #[cfg(not(feature = "dummy"))]
 mod std_impls;
 
+#[cfg(not(feature = "dummy"))]
+pub mod validate;
+
+#[cfg(not(feature = "dummy"))]
+mod ctx;
+
+#[cfg(not(feature = "dummy"))]
+pub use ctx::InspectCtx;
+
+#[cfg(not(feature = "dummy"))]
+mod inspector;
+
+#[cfg(not(feature = "dummy"))]
+pub use inspector::Inspector;
+
+#[cfg(not(feature = "dummy"))]
+pub mod path;
+
+#[cfg(not(feature = "dummy"))]
+pub mod visit;
+
+#[cfg(not(feature = "dummy"))]
+pub mod menu;
+
+#[cfg(not(feature = "dummy"))]
+pub mod filter;
+
+#[cfg(not(feature = "dummy"))]
+pub mod watch;
+
+#[cfg(not(feature = "dummy"))]
+pub mod watchpoint;
+
+#[cfg(not(feature = "dummy"))]
+mod text;
+
+#[cfg(not(feature = "dummy"))]
+pub mod persist;
+
+#[cfg(not(feature = "dummy"))]
+pub mod table;
+
+#[cfg(not(feature = "dummy"))]
+pub mod multi;
+
+#[cfg(not(feature = "dummy"))]
+pub mod diff;
+
+#[cfg(not(feature = "dummy"))]
+pub mod journal;
+
+#[cfg(not(feature = "dummy"))]
+pub mod tweak;
+
+#[cfg(not(feature = "dummy"))]
+pub mod registry;
+
+#[cfg(not(feature = "dummy"))]
+mod tag;
+
+#[cfg(not(feature = "dummy"))]
+pub use tag::Tag;
+
+/// Registers the literal (optionally negated) by the source location and returns the current value
+#[macro_export]
+#[cfg(not(feature = "dummy"))]
+macro_rules! tweak {
+    ($value:literal) => {
+        $crate::tweak::value(
+            $crate::tweak::Location {
+                file: file!(),
+                line: line!(),
+                column: column!(),
+            },
+            stringify!($value),
+            $value,
+        )
+    };
+}
+
+/// Registers the literal (optionally negated) by the source location and returns the current value
+#[macro_export]
+#[cfg(feature = "dummy")]
+macro_rules! tweak {
+    ($value:literal) => {
+        $value
+    };
+}
+
+/// Inspects the expressions, labelled with their source text, in the current window or in a
+/// window with the title
+///
+/// ```ignore
+/// igri::inspect!(ui, player.pos, speed, state);
+/// igri::inspect!(ui, window = "Locals", player.pos, speed);
+/// ```
+#[macro_export]
+#[cfg(not(feature = "dummy"))]
+macro_rules! inspect {
+    ($ui:expr, window = $title:expr, $($x:expr),+ $(,)?) => {
+        $crate::window($ui, $title, |ctx| {
+            $($crate::Inspect::inspect_ctx(&mut $x, ctx, $ui, stringify!($x));)+
+        })
+    };
+    ($ui:expr, $($x:expr),+ $(,)?) => {{
+        let ctx = &mut $crate::InspectCtx::new();
+        $($crate::Inspect::inspect_ctx(&mut $x, ctx, $ui, stringify!($x));)+
+    }};
+}
+
+/// Inspects the expressions, labelled with their source text, in the current window or in a
+/// window with the title
+#[macro_export]
+#[cfg(feature = "dummy")]
+macro_rules! inspect {
+    ($ui:expr, window = $title:expr, $($x:expr),+ $(,)?) => {{
+        let _ = (&$ui, &$title);
+        $(let _ = &$x;)+
+    }};
+    ($ui:expr, $($x:expr),+ $(,)?) => {{
+        let _ = &$ui;
+        $(let _ = &$x;)+
+    }};
+}
+
 // Derive macro can have same name as trait
 pub use igri_derive::Inspect;
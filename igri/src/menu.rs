@@ -0,0 +1,186 @@
+/*!
+Per-field context menu: copy, paste, reset, watch, watchpoints and copy path
+*/
+
+use std::{cell::RefCell, collections::HashMap};
+
+use imgui::{MenuItem, MouseButton, Ui};
+
+use crate::{
+    journal, path,
+    visit::{self, Entries},
+    watch, watchpoint, Inspect, InspectCtx,
+};
+
+thread_local! {
+    /// Type name and the leaf values of the copied value
+    static CLIPBOARD: RefCell<Option<(&'static str, Entries)>> = const { RefCell::new(None) };
+    /// Root path → leaf values on the first inspection
+    static INITIAL: RefCell<HashMap<String, Entries>> = RefCell::new(HashMap::new());
+}
+
+/// Value of the context menu target
+pub struct MenuTarget<'a> {
+    type_name: &'static str,
+    value: &'a mut dyn Inspect,
+}
+
+impl<'a> MenuTarget<'a> {
+    pub fn new<T: Inspect>(value: &'a mut T) -> Self {
+        Self {
+            type_name: std::any::type_name::<T>(),
+            value,
+        }
+    }
+
+    pub(crate) fn value(&mut self) -> &mut dyn Inspect {
+        self.value
+    }
+}
+
+/// Remembers the leaf values of the root value on the first inspection, for `Reset to initial`
+pub(crate) fn capture_initial(root_path: &str, value: &mut dyn Inspect) {
+    if INITIAL.with(|map| map.borrow().contains_key(root_path)) {
+        return;
+    }
+
+    let entries = visit::collect(value);
+    INITIAL.with(|map| map.borrow_mut().insert(root_path.to_string(), entries));
+}
+
+/// Leaf values of the path on the first inspection
+fn initial_entries(path: &str) -> Option<Entries> {
+    INITIAL.with(|map| {
+        let map = map.borrow();
+        let (root, entries) = map
+            .iter()
+            .find(|(root, _)| path::strip(root, path).is_some())?;
+        let base = path::strip(root, path)?;
+
+        let entries = entries
+            .iter()
+            .filter_map(|(p, text)| {
+                let rel = path::strip(base, p)?;
+                Some((rel.to_string(), text.clone()))
+            })
+            .collect::<Vec<_>>();
+
+        Some(entries)
+    })
+}
+
+/// Opens the context menu on right-clicking the last item (a tree node header or a widget)
+///
+/// The value actions are available if the target is given. Editing actions are disabled if the
+/// context is read-only.
+pub fn item_menu(ctx: &InspectCtx, ui: &Ui, target: Option<MenuTarget>) {
+    let popup_id = "##igri_menu";
+    if ui.is_item_clicked_with_button(MouseButton::Right) {
+        ui.open_popup(popup_id);
+    }
+
+    let token = match ui.begin_popup(popup_id) {
+        Some(token) => token,
+        None => return,
+    };
+
+    let path = ctx.path();
+
+    if let Some(mut target) = target {
+        if self::value_menu_items(ui, path, &mut target, !ctx.is_read_only()) {
+            // not an outside change
+            watchpoint::sync(path, target.value());
+            journal::record_value(path, target.value());
+        }
+        ui.separator();
+    }
+
+    let watched = watch::is_watched(path);
+    if MenuItem::new(if watched { "Unwatch" } else { "Watch" }).build(ui) {
+        if watched {
+            watch::unwatch(path);
+        } else {
+            watch::watch(path);
+        }
+    }
+
+    let has_watchpoint = watchpoint::is_set(path);
+    if MenuItem::new("Watchpoint")
+        .selected(has_watchpoint)
+        .build(ui)
+    {
+        if has_watchpoint {
+            watchpoint::unset(path);
+        } else {
+            watchpoint::set(path, false);
+        }
+    }
+
+    let breaks = watchpoint::breaks(path);
+    if MenuItem::new("Break on change").selected(breaks).build(ui) {
+        watchpoint::set(path, !breaks);
+    }
+
+    if MenuItem::new("Copy path").build(ui) {
+        ui.set_clipboard_text(path);
+    }
+
+    token.end();
+}
+
+/// Returns `true` if the value is edited
+fn value_menu_items(ui: &Ui, path: &str, target: &mut MenuTarget, editable: bool) -> bool {
+    let type_name = target.type_name;
+    let value = &mut *target.value;
+    let mut edited = false;
+
+    if MenuItem::new("Copy value").build(ui) {
+        let entries = visit::collect(value);
+        ui.set_clipboard_text(self::to_text(&entries));
+        CLIPBOARD.with(|c| *c.borrow_mut() = Some((type_name, entries)));
+    }
+
+    let can_paste =
+        editable && CLIPBOARD.with(|c| matches!(&*c.borrow(), Some((ty, _)) if *ty == type_name));
+    if MenuItem::new("Paste value").enabled(can_paste).build(ui) {
+        CLIPBOARD.with(|c| {
+            if let Some((_, entries)) = &*c.borrow() {
+                visit::apply(value, entries);
+            }
+        });
+        edited = true;
+    }
+
+    if MenuItem::new("Reset to default")
+        .enabled(editable)
+        .build(ui)
+    {
+        visit::reset(value);
+        edited = true;
+    }
+
+    let initial = self::initial_entries(path);
+    if MenuItem::new("Reset to initial")
+        .enabled(editable && initial.is_some())
+        .build(ui)
+    {
+        if let Some(entries) = initial {
+            visit::apply(value, &entries);
+            edited = true;
+        }
+    }
+
+    edited
+}
+
+/// `path = value` lines, or just the value for a leaf
+fn to_text(entries: &[(String, String)]) -> String {
+    match entries {
+        [(path, text)] if path.is_empty() => text.clone(),
+        _ => entries
+            .iter()
+            .map(|(path, text)| format!("{} = {:?}", path, text))
+            .collect::<Vec<_>>()
+            .join("\n"),
+    }
+}
@@ -0,0 +1,204 @@
+/*!
+Multi-selection editing of sequence elements
+
+Enable it with `#[inspect(multi_select)]` on a sequence field. Check several elements, and the
+selection is inspected together: an edit is applied to all the selected elements, and the fields
+whose values differ are marked as mixed. It works on the leaf values visited by the derived
+[`Inspect::visit`], so no per-type code is needed.
+
+Selections are index-keyed: after the sequence is reordered (or an element is inserted or removed
+before them), the same indices refer to different elements.
+*/
+
+use std::{
+    cell::RefCell,
+    collections::{BTreeSet, HashMap, HashSet},
+    fmt::Write,
+};
+
+use imgui::Ui;
+
+use crate::{journal, menu::MenuTarget, path, visit, watchpoint, Inspect, InspectCtx};
+
+thread_local! {
+    /// Sequence path → selected indices
+    static SELECTIONS: RefCell<HashMap<String, BTreeSet<usize>>> = RefCell::new(HashMap::new());
+}
+
+/// Leaves whose values differ among the selected elements (user data of the [`InspectCtx`])
+#[derive(Debug)]
+struct Mixed {
+    /// Path of the inspected element
+    base: String,
+    /// Relative leaf paths
+    paths: HashSet<String>,
+}
+
+/// If the value at the current path differs among the selected elements
+pub(crate) fn is_mixed(ctx: &InspectCtx) -> bool {
+    ctx.get::<Mixed>()
+        .and_then(|mixed| {
+            let rel = path::strip(&mixed.base, ctx.path())?;
+            Some(mixed.paths.contains(rel))
+        })
+        .unwrap_or(false)
+}
+
+/// Selected indices of the sequence at the path (not following the elements on reorder)
+pub fn selection(path: &str) -> BTreeSet<usize> {
+    SELECTIONS.with(|s| s.borrow().get(path).cloned().unwrap_or_default())
+}
+
+pub fn set_selection(path: &str, selection: BTreeSet<usize>) {
+    SELECTIONS.with(|s| s.borrow_mut().insert(path.to_string(), selection));
+}
+
+/// Standard method to inspect a sequence with multi-selection editing
+pub fn multi_select<S, T>(xs: &mut S, ctx: &mut InspectCtx, ui: &Ui, label: &str)
+where
+    S: Inspect,
+    for<'a> &'a mut S: IntoIterator<Item = &'a mut T>,
+    T: Inspect,
+{
+    ctx.root(label, |ctx| {
+        let _token = match crate::tree_node(ctx, ui, label, false, Some(MenuTarget::new(xs))) {
+            Some(token) => token,
+            None => return,
+        };
+
+        let key = ctx.path().to_string();
+        let mut rows = xs.into_iter().collect::<Vec<_>>();
+        let mut selection = self::selection(&key);
+        selection.retain(|&i| i < rows.len());
+
+        if selection.len() >= 2 {
+            self::edit_selection(&mut rows, &selection, ctx, ui);
+            ui.separator();
+        } else {
+            ui.text_disabled("Check elements to edit them together");
+        }
+
+        crate::seq_items_by(
+            rows.iter_mut().map(|x| &mut **x).enumerate(),
+            ctx,
+            ui,
+            |buf, i, _x| write!(buf, "{}", i),
+            |i, x| x.inspect_id().unwrap_or(i as u64),
+            |i| {
+                let mut selected = selection.contains(&i);
+                if ui.checkbox("##igri_select", &mut selected) {
+                    if selected {
+                        selection.insert(i);
+                    } else {
+                        selection.remove(&i);
+                    }
+                }
+                ui.same_line();
+            },
+        );
+
+        self::set_selection(&key, selection);
+    });
+}
+
+/// Inspects the first selected element, marking the mixed values, and applies the edits to the
+/// other selected elements
+fn edit_selection<T: Inspect>(
+    rows: &mut [&mut T],
+    selection: &BTreeSet<usize>,
+    ctx: &mut InspectCtx,
+    ui: &Ui,
+) {
+    let first = *selection.iter().next().unwrap();
+
+    let before = visit::collect(rows[first]);
+    let paths = self::mixed_paths(rows, selection);
+
+    // `###`: keep the open state when the number of the selected elements changes
+    let label = format!("{} selected###igri_selection", selection.len());
+    {
+        let _id = ui.push_id("##igri_selection");
+        crate::index(ctx, ui, first, |ctx| {
+            let mixed = Mixed {
+                base: ctx.path().to_string(),
+                paths,
+            };
+            let outer = ctx.insert(mixed);
+            rows[first].inspect_ctx(ctx, ui, &label);
+            ctx.remove::<Mixed>();
+            if let Some(outer) = outer {
+                ctx.insert(outer);
+            }
+        });
+    }
+
+    let changed = self::propagate(rows, selection, &before);
+    if changed.is_empty() {
+        return;
+    }
+
+    for &i in selection.iter().skip(1) {
+        // not an outside change
+        ctx.index(i, |ctx| {
+            watchpoint::sync(ctx.path(), rows[i]);
+            journal::record(ctx.path(), &changed);
+        });
+    }
+}
+
+/// Relative leaf paths whose values differ between the first selected element and any other
+/// selected element
+pub fn mixed_paths<T: Inspect>(
+    rows: &mut [&mut T],
+    selection: &BTreeSet<usize>,
+) -> HashSet<String> {
+    let mut ixs = selection.iter().copied();
+    let first = match ixs.next() {
+        Some(first) => visit::collect(rows[first]),
+        None => return HashSet::new(),
+    };
+
+    let mut paths = HashSet::new();
+    for i in ixs {
+        let entries = visit::collect(rows[i])
+            .into_iter()
+            .collect::<HashMap<_, _>>();
+        for (path, text) in &first {
+            if entries.get(path) != Some(text) {
+                paths.insert(path.clone());
+            }
+        }
+    }
+
+    paths
+}
+
+/// Applies the leaf values of the first selected element changed since `before` to the other
+/// selected elements. Returns the changed leaves
+///
+/// An enum tag is visited before the variant fields, so a variant switch is propagated along with
+/// the new field values.
+pub fn propagate<T: Inspect>(
+    rows: &mut [&mut T],
+    selection: &BTreeSet<usize>,
+    before: &[(String, String)],
+) -> Vec<(String, String)> {
+    let mut ixs = selection.iter().copied();
+    let first = match ixs.next() {
+        Some(first) => first,
+        None => return Vec::new(),
+    };
+
+    let changed = visit::collect(rows[first])
+        .into_iter()
+        .filter(|entry| !before.contains(entry))
+        .collect::<Vec<_>>();
+
+    if !changed.is_empty() {
+        for i in ixs {
+            visit::apply(rows[i], &changed);
+        }
+    }
+
+    changed
+}
@@ -0,0 +1,107 @@
+/*!
+Field paths of inspected values, e.g. `entities[1].hp`
+
+Composite inspectors push a segment for each child to the [`crate::InspectCtx`] before inspecting it,
+so that any inspector can know where it is in the inspection tree. The root segment is the label of
+the root inspector.
+*/
+
+use std::fmt;
+
+/// Segment of a [`FieldPath`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Segment<'a> {
+    /// Named or tuple field: `.name`
+    Field(&'a str),
+    /// Sequence element: `[i]`
+    Index(usize),
+}
+
+/// Path string such as `entities[1].hp`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FieldPath {
+    buf: String,
+    /// Length of the buffer before each push
+    lens: Vec<usize>,
+}
+
+impl fmt::Display for FieldPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.buf)
+    }
+}
+
+impl FieldPath {
+    pub fn as_str(&self) -> &str {
+        &self.buf
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lens.is_empty()
+    }
+
+    /// Number of segments
+    pub fn depth(&self) -> usize {
+        self.lens.len()
+    }
+
+    pub fn push(&mut self, segment: Segment) {
+        use std::fmt::Write;
+
+        self.lens.push(self.buf.len());
+
+        match segment {
+            Segment::Field(name) => {
+                if !self.buf.is_empty() {
+                    self.buf.push('.');
+                }
+                self.buf.push_str(name);
+            }
+            Segment::Index(i) => {
+                write!(self.buf, "[{}]", i).unwrap();
+            }
+        }
+    }
+
+    /// Last segment without the leading `.`, e.g. `hp` or `[1]`
+    pub fn last(&self) -> &str {
+        match self.lens.last() {
+            Some(&len) => {
+                let last = &self.buf[len..];
+                last.strip_prefix('.').unwrap_or(last)
+            }
+            None => "",
+        }
+    }
+
+    pub fn pop(&mut self) {
+        if let Some(len) = self.lens.pop() {
+            self.buf.truncate(len);
+        }
+    }
+}
+
+/// Joins a base path and a path relative to it
+pub fn join(base: &str, rel: &str) -> String {
+    if rel.is_empty() {
+        base.to_string()
+    } else if base.is_empty() || rel.starts_with('[') {
+        format!("{}{}", base, rel)
+    } else {
+        format!("{}.{}", base, rel)
+    }
+}
+
+/// Strips the base path, returning the path relative to it
+pub fn strip<'a>(base: &str, path: &'a str) -> Option<&'a str> {
+    if base.is_empty() {
+        return Some(path);
+    }
+
+    let rel = path.strip_prefix(base)?;
+    if rel.is_empty() || rel.starts_with('[') {
+        Some(rel)
+    } else {
+        rel.strip_prefix('.')
+    }
+}
@@ -0,0 +1,247 @@
+/*!
+Persistence of the inspector state: expanded nodes, pinned paths and window filters
+
+The state is keyed by field paths (e.g. `entities[1].stats`) rather than ImGUI IDs, so it survives
+code reordering. Set a file with [`set_file`] at startup; the state in the file is restored
+immediately. Changes are written by the inspector windows at most once per [`SAVE_INTERVAL`]
+(debounced), so the last changes may not be written yet: call [`flush`] before exiting.
+
+The file is a small text file with a tab-separated entry per line: `open <path>`,
+`closed <path>`, `pin <path>` or `filter <window title> <query> <values>`. Tabs and newlines in the
+fields are escaped with `\`.
+
+Window positions and sizes are not included; they're saved by ImGUI's `.ini` file.
+*/
+
+use std::{
+    cell::RefCell,
+    collections::BTreeMap,
+    fs, io,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use crate::{
+    filter::Filter,
+    text::{escape, unescape},
+    watch,
+};
+
+/// Minimum interval between the writes of the changed state by the inspector windows
+pub const SAVE_INTERVAL: Duration = Duration::from_secs(1);
+
+thread_local! {
+    static PERSIST: RefCell<Persist> = const {
+        RefCell::new(Persist {
+            file: None,
+            open: BTreeMap::new(),
+            pending: None,
+            last_save: None,
+        })
+    };
+}
+
+#[derive(Debug)]
+struct Persist {
+    file: Option<PathBuf>,
+    /// Path → open state of the nodes that differ from their default
+    open: BTreeMap<String, bool>,
+    /// Text of the changed state to write
+    pending: Option<String>,
+    last_save: Option<Instant>,
+}
+
+impl Persist {
+    /// Writes the pending text if any
+    fn write_pending(&mut self) -> io::Result<()> {
+        let (file, text) = match (self.file.as_ref(), self.pending.take()) {
+            (Some(file), Some(text)) => (file, text),
+            _ => return Ok(()),
+        };
+
+        self.last_save = Some(Instant::now());
+        fs::write(file, text)
+    }
+}
+
+/// Persisted inspector state
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct State {
+    /// Path → open state of the tree nodes
+    pub open: BTreeMap<String, bool>,
+    /// Pinned paths of the watch window
+    pub pins: Vec<String>,
+    /// Window title → filter of [`crate::window`]s
+    pub filters: BTreeMap<String, Filter>,
+}
+
+impl State {
+    /// Current state
+    pub fn current() -> Self {
+        Self {
+            open: PERSIST.with(|p| p.borrow().open.clone()),
+            pins: watch::pins(),
+            filters: crate::filter::all(),
+        }
+    }
+
+    /// Applies the state: opens the nodes on their next inspection, pins the paths and sets the
+    /// window filters
+    pub fn restore(&self) {
+        PERSIST.with(|p| p.borrow_mut().open.extend(self.open.clone()));
+
+        for pin in &self.pins {
+            watch::watch(pin);
+        }
+
+        for (title, filter) in &self.filters {
+            crate::filter::save(title, filter);
+        }
+    }
+
+    pub fn read(file: &Path) -> io::Result<Self> {
+        let text = fs::read_to_string(file)?;
+        Ok(Self::parse(&text))
+    }
+
+    pub fn write(&self, file: &Path) -> io::Result<()> {
+        fs::write(file, self.to_text())
+    }
+
+    /// Parses the text, skipping invalid lines
+    pub fn parse(text: &str) -> Self {
+        let mut state = Self::default();
+
+        for line in text.lines() {
+            let cols = line.split('\t').map(unescape).collect::<Vec<_>>();
+            let cols = cols.iter().map(String::as_str).collect::<Vec<_>>();
+            match cols.as_slice() {
+                ["open", path] => {
+                    state.open.insert(path.to_string(), true);
+                }
+                ["closed", path] => {
+                    state.open.insert(path.to_string(), false);
+                }
+                ["pin", path] => {
+                    state.pins.push(path.to_string());
+                }
+                ["filter", title, query, values] => {
+                    let filter = Filter {
+                        query: query.to_string(),
+                        values: *values == "true",
+                    };
+                    state.filters.insert(title.to_string(), filter);
+                }
+                _ => {}
+            }
+        }
+
+        state
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut lines = Vec::new();
+
+        for (path, open) in &self.open {
+            let kind = if *open { "open" } else { "closed" };
+            lines.push(format!("{}\t{}", kind, escape(path)));
+        }
+
+        for pin in &self.pins {
+            lines.push(format!("pin\t{}", escape(pin)));
+        }
+
+        for (title, filter) in &self.filters {
+            lines.push(format!(
+                "filter\t{}\t{}\t{}",
+                escape(title),
+                escape(&filter.query),
+                filter.values
+            ));
+        }
+
+        let mut text = lines.join("\n");
+        text.push('\n');
+        text
+    }
+}
+
+/// Sets the file to persist the inspector state. The state in the file is restored immediately,
+/// and the changes are written to the file later (see the module documentation)
+pub fn set_file(file: Option<impl Into<PathBuf>>) {
+    let file = file.map(Into::into);
+
+    if let Some(file) = file.as_ref() {
+        // the file does not exist on the first run
+        if let Ok(state) = State::read(file) {
+            state.restore();
+        }
+    }
+
+    PERSIST.with(|p| {
+        let mut p = p.borrow_mut();
+        p.file = file;
+        p.pending = None;
+    });
+}
+
+/// Saves the current state to the file if it's set
+pub fn save() -> io::Result<()> {
+    if PERSIST.with(|p| p.borrow().file.is_none()) {
+        return Ok(());
+    }
+
+    let text = State::current().to_text();
+    PERSIST.with(|p| {
+        let mut p = p.borrow_mut();
+        p.pending = Some(text);
+        p.write_pending()
+    })
+}
+
+/// Saved open state of the node
+pub(crate) fn is_open(path: &str) -> Option<bool> {
+    PERSIST.with(|p| p.borrow().open.get(path).copied())
+}
+
+/// Records the open state of the node, only keeping the ones that differ from the default
+pub(crate) fn set_open(path: &str, open: bool, default_open: bool) {
+    let changed = PERSIST.with(|p| {
+        let mut p = p.borrow_mut();
+        if open == default_open {
+            p.open.remove(path).is_some()
+        } else {
+            p.open.insert(path.to_string(), open) != Some(open)
+        }
+    });
+
+    if changed {
+        self::on_change();
+    }
+}
+
+/// Marks the state to be written to the file
+pub(crate) fn on_change() {
+    if PERSIST.with(|p| p.borrow().file.is_none()) {
+        return;
+    }
+
+    let text = State::current().to_text();
+    PERSIST.with(|p| p.borrow_mut().pending = Some(text));
+}
+
+/// Writes the changed state to the file now
+pub fn flush() -> io::Result<()> {
+    PERSIST.with(|p| p.borrow_mut().write_pending())
+}
+
+/// Writes the changed state to the file if [`SAVE_INTERVAL`] has passed since the last write.
+/// Called by the inspector windows every frame. Errors are ignored, as the state is not critical
+pub(crate) fn flush_debounced() {
+    PERSIST.with(|p| {
+        let mut p = p.borrow_mut();
+        if p.pending.is_some() && p.last_save.is_none_or(|t| t.elapsed() >= SAVE_INTERVAL) {
+            let _ = p.write_pending();
+        }
+    });
+}
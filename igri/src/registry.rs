@@ -0,0 +1,210 @@
+/*!
+Type registry for inspecting trait objects: `Box<dyn Any>`, `&mut dyn Any`, ..
+
+Register the concrete types with [`register`], and `dyn Any` values are inspected by the inspectors
+of their concrete types, labelled with the type names:
+
+```ignore
+igri::registry::register::<Enemy>();
+igri::registry::register::<Item>();
+
+#[derive(Inspect)]
+pub struct Scene {
+    objects: Vec<Box<dyn Any>>,
+}
+```
+
+Values of unregistered types are shown as `<unregistered TypeName>`, with the `TypeId` as the
+tooltip. `Any` does not expose the type name, so it is known only for the types registered before or
+boxed with [`boxed`]; other values are shown as `<unregistered>`. Use `Box<dyn igri::DynInspect>`
+instead to show the type names without registration.
+*/
+
+use std::{
+    any::{self, Any, TypeId},
+    cell::RefCell,
+    collections::HashMap,
+};
+
+use imgui::Ui;
+
+use crate::{visit::Visitor, Inspect, InspectCtx};
+
+thread_local! {
+    static REGISTRY: RefCell<HashMap<TypeId, Entry>> = RefCell::new(HashMap::new());
+    /// Short type names, leaked once per type and kept on unregistration
+    static NAMES: RefCell<HashMap<TypeId, &'static str>> = RefCell::new(HashMap::new());
+    /// `<unregistered TypeName>` labels, leaked once per type
+    static UNREGISTERED: RefCell<HashMap<TypeId, &'static str>> = RefCell::new(HashMap::new());
+}
+
+/// Type-erased inspector of a registered type
+#[derive(Clone, Copy)]
+struct Entry {
+    name: &'static str,
+    inspect: fn(&mut dyn Any, &mut InspectCtx, &Ui, &str),
+    visit: fn(&mut dyn Any, &mut dyn Visitor),
+}
+
+/// Registers the type to inspect its values behind `dyn Any`
+pub fn register<T: Inspect + Any>() {
+    let name = self::add_name::<T>();
+    let entry = Entry {
+        name,
+        inspect: self::inspect_any::<T>,
+        visit: self::visit_any::<T>,
+    };
+    REGISTRY.with(|r| r.borrow_mut().insert(TypeId::of::<T>(), entry));
+}
+
+/// Boxes the value, remembering its type name to label it while the type is unregistered
+pub fn boxed<T: Any>(x: T) -> Box<dyn Any> {
+    self::add_name::<T>();
+    Box::new(x)
+}
+
+pub fn unregister<T: Any>() {
+    REGISTRY.with(|r| r.borrow_mut().remove(&TypeId::of::<T>()));
+}
+
+pub fn is_registered<T: Any>() -> bool {
+    REGISTRY.with(|r| r.borrow().contains_key(&TypeId::of::<T>()))
+}
+
+/// Type name of the value if its type is registered (without the module path)
+///
+/// Returns `None` for unregistered types, as `dyn Any` has no type name, only the `TypeId`.
+pub fn type_name(x: &dyn Any) -> Option<&'static str> {
+    self::entry(x.type_id()).map(|entry| entry.name)
+}
+
+/// Label of the value of an unregistered type: `<unregistered TypeName>` if the type name is known
+fn unregistered_name(x: &dyn Any) -> &'static str {
+    let id = (*x).type_id();
+    let name = match NAMES.with(|names| names.borrow().get(&id).copied()) {
+        Some(name) => name,
+        None => return "<unregistered>",
+    };
+
+    UNREGISTERED.with(|labels| {
+        *labels
+            .borrow_mut()
+            .entry(id)
+            .or_insert_with(|| Box::leak(format!("<unregistered {}>", name).into_boxed_str()))
+    })
+}
+
+/// Remembers the short type name of `T`
+fn add_name<T: Any>() -> &'static str {
+    NAMES.with(|names| {
+        *names
+            .borrow_mut()
+            .entry(TypeId::of::<T>())
+            .or_insert_with(|| self::short_name(any::type_name::<T>()))
+    })
+}
+
+fn entry(id: TypeId) -> Option<Entry> {
+    // copied out, so that the inspectors can register types
+    REGISTRY.with(|r| r.borrow().get(&id).copied())
+}
+
+fn inspect_any<T: Inspect + Any>(x: &mut dyn Any, ctx: &mut InspectCtx, ui: &Ui, label: &str) {
+    if let Some(x) = x.downcast_mut::<T>() {
+        x.inspect_ctx(ctx, ui, label);
+    }
+}
+
+fn visit_any<T: Inspect + Any>(x: &mut dyn Any, visitor: &mut dyn Visitor) {
+    if let Some(x) = x.downcast_mut::<T>() {
+        x.visit(visitor);
+    }
+}
+
+/// Strips the module paths from the type name: `Vec<game::Enemy>` → `Vec<Enemy>`
+fn short_name(name: &'static str) -> &'static str {
+    if !name.contains("::") {
+        return name;
+    }
+
+    let mut short = String::new();
+    let mut segment = String::new();
+    for c in name.chars() {
+        if c.is_alphanumeric() || c == '_' || c == ':' {
+            segment.push(c);
+        } else {
+            short.push_str(segment.rsplit("::").next().unwrap_or(""));
+            segment.clear();
+            short.push(c);
+        }
+    }
+    short.push_str(segment.rsplit("::").next().unwrap_or(""));
+
+    Box::leak(short.into_boxed_str())
+}
+
+/// Inspects the value with the inspector of the registered type
+fn inspect_dyn(x: &mut dyn Any, ctx: &mut InspectCtx, ui: &Ui, label: &str) {
+    match self::entry((*x).type_id()) {
+        Some(entry) => (entry.inspect)(x, ctx, ui, label),
+        None => {
+            ui.text_disabled(label);
+            if ui.is_item_hovered() {
+                ui.tooltip_text(format!("{:?}", (*x).type_id()));
+            }
+        }
+    }
+}
+
+fn visit_dyn(x: &mut dyn Any, visitor: &mut dyn Visitor) {
+    if let Some(entry) = self::entry((*x).type_id()) {
+        (entry.visit)(x, visitor);
+    }
+}
+
+macro_rules! impl_dyn_any {
+    ($($ty:ty),*) => {
+        $(
+            /// Inspected by the inspector of the registered type. Unregistered types are labelled
+            /// `<unregistered TypeName>`, or `<unregistered>` if the type name is not known
+            impl Inspect for $ty {
+                fn inspect_ctx(&mut self, ctx: &mut InspectCtx, ui: &Ui, label: &str) {
+                    self::inspect_dyn(self, ctx, ui, label);
+                }
+
+                fn visit(&mut self, visitor: &mut dyn Visitor) {
+                    self::visit_dyn(self, visitor);
+                }
+
+                fn inspect_type_name(&self) -> &'static str {
+                    self::type_name(self).unwrap_or_else(|| self::unregistered_name(self))
+                }
+            }
+        )*
+    };
+}
+
+impl_dyn_any!(dyn Any, dyn Any + Send, dyn Any + Send + Sync);
+
+macro_rules! impl_dyn_any_mut {
+    ($($ty:ty),*) => {
+        $(
+            /// Labelled with the type name like `Box<dyn Any>`
+            impl<'a> Inspect for &'a mut $ty {
+                fn inspect_ctx(&mut self, ctx: &mut InspectCtx, ui: &Ui, label: &str) {
+                    crate::inspect_dyn(&mut **self, ctx, ui, label);
+                }
+
+                fn visit(&mut self, visitor: &mut dyn Visitor) {
+                    (**self).visit(visitor);
+                }
+
+                fn inspect_type_name(&self) -> &'static str {
+                    (**self).inspect_type_name()
+                }
+            }
+        )*
+    };
+}
+
+impl_dyn_any_mut!(dyn Any, dyn Any + Send, dyn Any + Send + Sync);
@@ -10,63 +10,167 @@ use std::{
     collections::{LinkedList, VecDeque},
     marker::PhantomData,
     num::*,
-    ops::DerefMut,
+    ops::{Deref, DerefMut},
     path::PathBuf,
     time::{Duration, Instant},
 };
 
 use imgui::Ui;
 
-use crate::Inspect;
+use crate::{
+    menu::MenuTarget,
+    visit::{self, Leaf, Visitor},
+    DynInspect, Inspect, InspectCtx, Tag,
+};
+
+// leaves
+
+/// impl Leaf for types that implement `FromStr` and `Display`
+macro_rules! impl_leaf {
+    ($ty:ty, $default:expr) => {
+        impl Leaf for $ty {
+            fn to_text(&self) -> String {
+                self.to_string()
+            }
+
+            fn set_text(&mut self, text: &str) -> bool {
+                match text.trim().parse() {
+                    Ok(x) => {
+                        *self = x;
+                        true
+                    }
+                    Err(_) => false,
+                }
+            }
+
+            fn reset(&mut self) {
+                *self = $default;
+            }
+        }
+    };
+}
+
+/// Visits `self` as a leaf
+macro_rules! visit_leaf {
+    () => {
+        fn visit(&mut self, visitor: &mut dyn Visitor) {
+            visitor.leaf(self);
+        }
+    };
+}
 
 // primitives
 
 macro_rules! im_ui_method {
     ($ty:ident, $method:ident) => {
+        impl_leaf!($ty, Default::default());
+
         impl Inspect for $ty {
-            fn inspect(&mut self, ui: &$crate::imgui::Ui, label: &str) {
-                let _changed = ui.$method(label, self);
+            fn inspect_ctx(&mut self, ctx: &mut InspectCtx, ui: &$crate::imgui::Ui, label: &str) {
+                $crate::leaf(ctx, ui, label, self, |x| {
+                    let _changed = ui.$method(label, x);
+                });
             }
+
+            visit_leaf!();
         }
     };
 }
 
 im_ui_method!(bool, checkbox);
 
+impl Leaf for String {
+    fn to_text(&self) -> String {
+        self.clone()
+    }
+
+    fn set_text(&mut self, text: &str) -> bool {
+        *self = text.to_string();
+        true
+    }
+
+    fn reset(&mut self) {
+        self.clear();
+    }
+}
+
 impl Inspect for String {
-    fn inspect(&mut self, ui: &imgui::Ui, label: &str) {
+    fn inspect_ctx(&mut self, ctx: &mut InspectCtx, ui: &imgui::Ui, label: &str) {
         // FIXME: Consider supporting multiline text
         // https://docs.rs/imgui/latest/imgui/struct.InputTextMultiline.html
-        let _changed = ui.input_text(label, self).build();
+        crate::leaf(ctx, ui, label, self, |s| {
+            let _changed = ui.input_text(label, s).build();
+        });
     }
+
+    visit_leaf!();
 }
 
 // TODO: char?
 
 macro_rules! im_input {
     ($ty:ident, $as:ty, $method:ident) => {
+        impl_leaf!($ty, Default::default());
+
         impl Inspect for $ty {
-            fn inspect(&mut self, ui: &$crate::imgui::Ui, label: &str) {
-                let mut x = *self as $as;
-                if ui.$method(format!("{}", label), &mut x).build() {
-                    *self = x as $ty;
-                }
+            fn inspect_ctx(&mut self, ctx: &mut InspectCtx, ui: &$crate::imgui::Ui, label: &str) {
+                $crate::leaf(ctx, ui, label, self, |this| {
+                    let mut x = *this as $as;
+                    if ui.$method(format!("{}", label), &mut x).build() {
+                        *this = x as $ty;
+                    }
+                });
             }
+
+            visit_leaf!();
         }
     };
 }
 
 macro_rules! impl_array {
     ($ty:ty, $N:expr, $as:ty, $method:ident) => {
+        impl Leaf for [$ty; $N] {
+            fn to_text(&self) -> String {
+                format!("{:?}", self)
+            }
+
+            fn set_text(&mut self, text: &str) -> bool {
+                let text = text.trim().trim_start_matches('[').trim_end_matches(']');
+                let xs = text
+                    .split(',')
+                    .map(|x| x.trim().parse::<$ty>())
+                    .collect::<Result<Vec<_>, _>>();
+
+                match xs
+                    .ok()
+                    .and_then(|xs| std::convert::TryInto::<[$ty; $N]>::try_into(xs).ok())
+                {
+                    Some(xs) => {
+                        *self = xs;
+                        true
+                    }
+                    None => false,
+                }
+            }
+
+            fn reset(&mut self) {
+                *self = Default::default();
+            }
+        }
+
         impl Inspect for [$ty; $N] {
             #[allow(warnings)]
-            fn inspect(&mut self, ui: &$crate::imgui::Ui, label: &str) {
-                let mut xs = self.clone().map(|x| x as $as);
-                let label = format!("{}", label);
-                if ui.$method(label, &mut xs).build() {
-                    *self = xs.map(|x| x as $ty);
-                }
+            fn inspect_ctx(&mut self, ctx: &mut InspectCtx, ui: &$crate::imgui::Ui, label: &str) {
+                $crate::leaf(ctx, ui, label, self, |this| {
+                    let mut xs = this.clone().map(|x| x as $as);
+                    let label = format!("{}", label);
+                    if ui.$method(label, &mut xs).build() {
+                        *this = xs.map(|x| x as $ty);
+                    }
+                });
             }
+
+            visit_leaf!();
         }
     };
     ($ty:ty, $as:ty, $method:ident) => {
@@ -103,13 +207,24 @@ macro_rules! impl_tuple {
             where
                 $([<T $i>]: Inspect,)*
             {
-                fn inspect(&mut self, ui: &Ui, label: &str) {
-                    $crate::nest(ui, label, || {
+                fn inspect_ctx(&mut self, ctx: &mut InspectCtx, ui: &Ui, label: &str) {
+                    ctx.root(label, |ctx| {
+                        let _token = match $crate::tree_node(ctx, ui, label, false, Some(MenuTarget::new(self))) {
+                            Some(token) => token,
+                            None => return,
+                        };
+
                         $(
-                            self.$i.inspect(ui, stringify!($i));
+                            $crate::field(ctx, ui, stringify!($i), |ctx| self.$i.inspect_ctx(ctx, ui, stringify!($i)));
                         )*
                     });
                 }
+
+                fn visit(&mut self, visitor: &mut dyn Visitor) {
+                    $(
+                        visit::visit_field(visitor, stringify!($i), &mut self.$i);
+                    )*
+                }
             }
         }
     };
@@ -124,13 +239,42 @@ impl_tuple!(0, 1, 2, 3);
 macro_rules! impl_non_zero {
     ($ty:ident) => {
         impl Inspect for $ty {
-            fn inspect(&mut self, ui: &Ui, label: &str) {
-                let mut x = self.clone().get();
-                x.inspect(ui, label);
-                if let Some(new_value) = $ty::new(x) {
+            fn inspect_ctx(&mut self, ctx: &mut InspectCtx, ui: &Ui, label: &str) {
+                let mut x = self.get();
+                x.inspect_ctx(ctx, ui, label);
+
+                let rejected = if x == self.get() {
+                    None
+                } else if let Some(new_value) = $ty::new(x) {
                     *self = new_value;
+                    None
+                } else {
+                    Some("must be non-zero")
+                };
+
+                ctx.root(label, |ctx| $crate::validate::reject_edit(ctx, ui, rejected));
+            }
+
+            visit_leaf!();
+        }
+
+        impl Leaf for $ty {
+            fn to_text(&self) -> String {
+                self.to_string()
+            }
+
+            fn set_text(&mut self, text: &str) -> bool {
+                match text.trim().parse() {
+                    Ok(x) => {
+                        *self = x;
+                        true
+                    }
+                    Err(_) => false,
                 }
             }
+
+            /// Non-zero types don't have default values
+            fn reset(&mut self) {}
         }
     };
     ($($ty:ident),* $(,)?) => {
@@ -147,44 +291,55 @@ impl_non_zero!(
 // None
 
 impl<T> Inspect for [T; 0] {
-    fn inspect(&mut self, _ui: &Ui, _label: &str) {}
+    fn inspect_ctx(&mut self, _ctx: &mut InspectCtx, _ui: &Ui, _label: &str) {}
+}
+
+fn option_tag<T: Default>(x: &mut Option<T>) -> Tag<'_, Option<T>> {
+    Tag {
+        value: x,
+        names: &["None", "Some"],
+        index: |x| if x.is_some() { 1 } else { 0 },
+        set: Some(|x, ix| {
+            *x = match ix {
+                0 => None,
+                _ => Some(Default::default()),
+            }
+        }),
+    }
 }
 
 impl<T: Inspect + Default> Inspect for Option<T> {
-    fn inspect(&mut self, ui: &Ui, label: &str) {
-        const NAMES: &'static [&'static str] = &["None", "Some"];
+    fn inspect_ctx(&mut self, ctx: &mut InspectCtx, ui: &Ui, label: &str) {
+        ctx.root(label, |ctx| {
+            let _token = match crate::tree_node(ctx, ui, label, false, Some(MenuTarget::new(self)))
+            {
+                Some(token) => token,
+                None => return,
+            };
 
-        crate::nest(ui, label, || {
             // tag
-            let mut ix = if self.is_some() { 1 } else { 0 };
-
-            if ui.combo("tag", &mut ix, NAMES, |name| {
-                match name {
-                    x if *x == "None" => "None",
-                    x if *x == "Some" => "Some",
-                    _ => unreachable!(),
-                }
-                .into()
-            }) {
-                match ix {
-                    x if x == 0 => *self = Self::None,
-                    x if x == 1 => *self = Self::Some(Default::default()),
-                    _ => unreachable!(),
-                }
-            }
+            crate::field(ctx, ui, "tag", |ctx| {
+                self::option_tag(self).inspect_ctx(ctx, ui, "tag")
+            });
 
             // fields
-            match self {
-                Self::Some(x) => x.inspect(ui, "data"),
-                Self::None => {}
+            if let Self::Some(x) = self {
+                crate::field(ctx, ui, "data", |ctx| x.inspect_ctx(ctx, ui, "data"));
             }
-            // fields
         });
     }
+
+    fn visit(&mut self, visitor: &mut dyn Visitor) {
+        visit::visit_field(visitor, "tag", &mut self::option_tag(self));
+
+        if let Self::Some(x) = self {
+            visit::visit_field(visitor, "data", x);
+        }
+    }
 }
 
 impl<T> Inspect for PhantomData<T> {
-    fn inspect(&mut self, _ui: &Ui, _label: &str) {}
+    fn inspect_ctx(&mut self, _ctx: &mut InspectCtx, _ui: &Ui, _label: &str) {}
 }
 
 // Wrappers
@@ -193,21 +348,75 @@ impl<'a, T: std::borrow::ToOwned + ?Sized> Inspect for Cow<'a, T>
 where
     T::Owned: Inspect,
 {
-    fn inspect(&mut self, ui: &Ui, label: &str) {
-        self.to_mut().inspect(ui, label);
+    fn inspect_ctx(&mut self, ctx: &mut InspectCtx, ui: &Ui, label: &str) {
+        self.to_mut().inspect_ctx(ctx, ui, label);
+    }
+
+    fn visit(&mut self, visitor: &mut dyn Visitor) {
+        self.to_mut().visit(visitor);
     }
 }
 
 impl<T: Inspect + ?Sized> Inspect for Box<T> {
-    fn inspect(&mut self, ui: &Ui, label: &str) {
-        self.deref_mut().inspect(ui, label);
+    fn inspect_ctx(&mut self, ctx: &mut InspectCtx, ui: &Ui, label: &str) {
+        // trait objects are labelled with the type names of the values
+        if crate::is_unsized::<T>() {
+            crate::inspect_dyn(self.deref_mut(), ctx, ui, label);
+        } else {
+            self.deref_mut().inspect_ctx(ctx, ui, label);
+        }
+    }
+
+    fn visit(&mut self, visitor: &mut dyn Visitor) {
+        self.deref_mut().visit(visitor);
     }
+
+    fn inspect_id(&self) -> Option<u64> {
+        self.deref().inspect_id()
+    }
+
+    fn inspect_type_name(&self) -> &'static str {
+        self.deref().inspect_type_name()
+    }
+}
+
+macro_rules! impl_dyn_mut {
+    ($($ty:ty),*) => {
+        $(
+            /// Labelled with the type name of the value like `Box<dyn Inspect>`
+            impl<'a> Inspect for &'a mut $ty {
+                fn inspect_ctx(&mut self, ctx: &mut InspectCtx, ui: &Ui, label: &str) {
+                    crate::inspect_dyn(&mut **self, ctx, ui, label);
+                }
+
+                fn visit(&mut self, visitor: &mut dyn Visitor) {
+                    (**self).visit(visitor);
+                }
+
+                fn inspect_id(&self) -> Option<u64> {
+                    (**self).inspect_id()
+                }
+
+                fn inspect_type_name(&self) -> &'static str {
+                    (**self).inspect_type_name()
+                }
+            }
+        )*
+    };
 }
 
+impl_dyn_mut!(dyn Inspect, dyn DynInspect);
+
 impl<T: Inspect + Copy> Inspect for Cell<T> {
-    fn inspect(&mut self, ui: &Ui, label: &str) {
+    fn inspect_ctx(&mut self, ctx: &mut InspectCtx, ui: &Ui, label: &str) {
+        let mut x = self.get();
+        x.inspect_ctx(ctx, ui, label);
+        self.set(x);
+    }
+
+    fn visit(&mut self, visitor: &mut dyn Visitor) {
         let mut x = self.get();
-        x.inspect(ui, label);
+        x.visit(visitor);
         self.set(x);
     }
 }
@@ -217,8 +426,18 @@ impl<T: Inspect + Copy> Inspect for Cell<T> {
 macro_rules! impl_seq {
     ($ty:ident) => {
         impl<T: Inspect> Inspect for $ty<T> {
-            fn inspect(&mut self, ui: &Ui, label: &str) {
-                crate::seq(self.iter_mut(), ui, label);
+            fn inspect_ctx(&mut self, ctx: &mut InspectCtx, ui: &Ui, label: &str) {
+                ctx.root(label, |ctx| {
+                    if let Some(_token) = crate::tree_node(ctx, ui, label, false, Some(MenuTarget::new(self))) {
+                        crate::seq_items(self.iter_mut().enumerate(), ctx, ui);
+                    }
+                });
+            }
+
+            fn visit(&mut self, visitor: &mut dyn Visitor) {
+                for (i, x) in self.iter_mut().enumerate() {
+                    visit::visit_index(visitor, i, x);
+                }
             }
         }
     };
@@ -233,24 +452,84 @@ impl_seq!(Vec, VecDeque, LinkedList);
 
 // more std types
 
+impl Leaf for Duration {
+    fn to_text(&self) -> String {
+        format!("{}", self.as_secs_f32())
+    }
+
+    fn set_text(&mut self, text: &str) -> bool {
+        // negative, infinite or too large values are rejected
+        match text.trim().parse::<f32>().map(Duration::try_from_secs_f32) {
+            Ok(Ok(x)) => {
+                *self = x;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn reset(&mut self) {
+        *self = Duration::ZERO;
+    }
+}
+
 impl Inspect for Duration {
-    fn inspect(&mut self, ui: &Ui, label: &str) {
-        let time = self.as_secs_f32();
-        ui.label_text(label, format!("{}", time));
+    fn inspect_ctx(&mut self, ctx: &mut InspectCtx, ui: &Ui, label: &str) {
+        crate::leaf(ctx, ui, label, self, |time| {
+            ui.label_text(label, time.to_text());
+        });
     }
+
+    visit_leaf!();
+}
+
+/// Read-only
+impl Leaf for Instant {
+    fn to_text(&self) -> String {
+        format!("{:?}", self)
+    }
+
+    fn set_text(&mut self, _text: &str) -> bool {
+        false
+    }
+
+    fn reset(&mut self) {}
 }
 
 impl Inspect for Instant {
-    fn inspect(&mut self, ui: &Ui, label: &str) {
-        ui.label_text(label, format!("{:?}", self));
+    fn inspect_ctx(&mut self, ctx: &mut InspectCtx, ui: &Ui, label: &str) {
+        crate::leaf(ctx, ui, label, self, |time| {
+            ui.label_text(label, time.to_text());
+        });
+    }
+
+    visit_leaf!();
+}
+
+impl Leaf for PathBuf {
+    fn to_text(&self) -> String {
+        format!("{}", self.display())
+    }
+
+    fn set_text(&mut self, text: &str) -> bool {
+        *self = PathBuf::from(text);
+        true
+    }
+
+    fn reset(&mut self) {
+        *self = PathBuf::new();
     }
 }
 
 impl Inspect for PathBuf {
-    fn inspect(&mut self, ui: &Ui, label: &str) {
-        let mut s = format!("{:?}", self);
-        if ui.input_text(label, &mut s).build() {
-            *self = PathBuf::from(s);
-        }
+    fn inspect_ctx(&mut self, ctx: &mut InspectCtx, ui: &Ui, label: &str) {
+        crate::leaf(ctx, ui, label, self, |path| {
+            let mut s = format!("{:?}", path);
+            if ui.input_text(label, &mut s).build() {
+                *path = PathBuf::from(s);
+            }
+        });
     }
+
+    visit_leaf!();
 }
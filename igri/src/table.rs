@@ -0,0 +1,174 @@
+/*!
+Spreadsheet view of sequences: a row per element and a column per field
+
+Enable it with `#[inspect(table)]` on a sequence field. The element type provides the columns with
+[`Columns`], which is derived for structs. Columns are resizable and sortable, and the rows can be
+filtered by their values.
+*/
+
+use std::{cell::RefCell, cmp::Ordering, collections::HashMap};
+
+use imgui::{TableColumnSetup, TableFlags, TableSortDirection, Ui};
+
+use crate::{filter::Filter, menu::MenuTarget, visit, Inspect, InspectCtx};
+
+thread_local! {
+    /// Table path → row filter
+    static ROW_FILTERS: RefCell<HashMap<String, String>> = RefCell::new(HashMap::new());
+}
+
+/// Column of a [`Columns`] type
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Column {
+    /// Path segment: the field name or the tuple index
+    pub name: &'static str,
+    /// Header text
+    pub label: &'static str,
+}
+
+/// Per-type column metadata for the table view (derived for structs)
+///
+/// Fields with `#[inspect(with = ..)]` or `#[inspect(as = ..)]` are not columns. Derived cells apply
+/// the field attributes (`min`, `max`, `validate`, `visible_if`, `enabled_if`, `tooltip`) just like the
+/// tree view.
+pub trait Columns: Inspect {
+    fn columns() -> &'static [Column];
+
+    /// The field of the column
+    fn column_mut(&mut self, column: usize) -> &mut dyn Inspect;
+
+    /// Draws the cell of the column. The column's path segment is already pushed to the context
+    fn inspect_column(&mut self, column: usize, ctx: &mut InspectCtx, ui: &Ui) {
+        let label = format!("##{}", Self::columns()[column].label);
+        self.column_mut(column).inspect_ctx(ctx, ui, &label);
+    }
+}
+
+/// Standard method to inspect a sequence as a table
+pub fn table<S, T>(xs: &mut S, ctx: &mut InspectCtx, ui: &Ui, label: &str)
+where
+    S: Inspect,
+    for<'a> &'a mut S: IntoIterator<Item = &'a mut T>,
+    T: Columns,
+{
+    ctx.root(label, |ctx| {
+        let _token = match crate::tree_node(ctx, ui, label, false, Some(MenuTarget::new(xs))) {
+            Some(token) => token,
+            None => return,
+        };
+
+        let filter = self::row_filter(ctx, ui);
+
+        let mut rows = xs.into_iter().collect::<Vec<_>>();
+        let columns = T::columns();
+
+        let flags = TableFlags::RESIZABLE
+            | TableFlags::SORTABLE
+            | TableFlags::BORDERS
+            | TableFlags::ROW_BG
+            | TableFlags::SIZING_STRETCH_PROP;
+        let _table = match ui.begin_table_with_flags("##igri_table", columns.len() + 1, flags) {
+            Some(table) => table,
+            None => return,
+        };
+
+        ui.table_setup_column_with(TableColumnSetup {
+            init_width_or_weight: 0.2,
+            ..TableColumnSetup::new("#")
+        });
+        for column in columns {
+            ui.table_setup_column(column.label);
+        }
+        ui.table_headers_row();
+
+        // the view is sorted, not the sequence
+        let mut order = (0..rows.len()).collect::<Vec<_>>();
+        if let Some((column, direction)) = self::sort_spec(ui) {
+            let keys = rows
+                .iter_mut()
+                .map(|row| match column {
+                    0 => Vec::new(),
+                    _ => self::cell_texts(row.column_mut(column - 1)),
+                })
+                .collect::<Vec<_>>();
+
+            order.sort_by(|&a, &b| {
+                let ord = match column {
+                    0 => a.cmp(&b),
+                    _ => self::compare(&keys[a], &keys[b]),
+                };
+                match direction {
+                    TableSortDirection::Ascending => ord,
+                    TableSortDirection::Descending => ord.reverse(),
+                }
+            });
+        }
+
+        for i in order {
+            let row = &mut *rows[i];
+
+            if filter.is_active() && !self::cell_texts(row).iter().any(|t| filter.matches(t)) {
+                continue;
+            }
+
+            ui.table_next_row();
+            ui.table_next_column();
+            ui.text(i.to_string());
+
+            crate::index(ctx, ui, i, |ctx| {
+                for (c, column) in columns.iter().enumerate() {
+                    ui.table_next_column();
+                    crate::field(ctx, ui, column.name, |ctx| {
+                        let _width = ui.push_item_width(-f32::MIN_POSITIVE);
+                        row.inspect_column(c, ctx, ui);
+                    });
+                }
+            });
+        }
+    });
+}
+
+/// Draws the row filter box of the table at the current path
+fn row_filter(ctx: &InspectCtx, ui: &Ui) -> Filter {
+    let key = ctx.path().to_string();
+    let mut query = ROW_FILTERS.with(|map| map.borrow().get(&key).cloned().unwrap_or_default());
+
+    if ui
+        .input_text("##igri_row_filter", &mut query)
+        .hint("filter rows")
+        .build()
+    {
+        ROW_FILTERS.with(|map| map.borrow_mut().insert(key, query.clone()));
+    }
+
+    Filter::new(query)
+}
+
+/// Column and direction of the primary sort
+fn sort_spec(ui: &Ui) -> Option<(usize, TableSortDirection)> {
+    let specs = ui.table_sort_specs_mut()?;
+    let spec = specs.specs().iter().next()?;
+    Some((spec.column_idx(), spec.sort_direction()?))
+}
+
+/// Leaf values of the cell
+fn cell_texts(x: &mut dyn Inspect) -> Vec<String> {
+    visit::collect(x)
+        .into_iter()
+        .map(|(_path, text)| text)
+        .collect()
+}
+
+/// Compares the texts as numbers if possible
+fn compare(a: &[String], b: &[String]) -> Ordering {
+    for (a, b) in a.iter().zip(b) {
+        let ord = match (a.parse::<f64>(), b.parse::<f64>()) {
+            (Ok(a), Ok(b)) => a.partial_cmp(&b).unwrap_or(Ordering::Equal),
+            _ => a.cmp(b),
+        };
+        if ord != Ordering::Equal {
+            return ord;
+        }
+    }
+    a.len().cmp(&b.len())
+}
@@ -0,0 +1,78 @@
+/*!
+Enum tag selector, shared by `Option` and derived `enum`s
+*/
+
+use imgui::Ui;
+
+use crate::{
+    visit::{Leaf, Visitor},
+    Inspect, InspectCtx,
+};
+
+/// Tag (variant) of an enum value, inspected as a combo box
+pub struct Tag<'a, T> {
+    pub value: &'a mut T,
+    /// Variant names
+    pub names: &'static [&'static str],
+    /// Index of the current variant
+    pub index: fn(&T) -> usize,
+    /// Replaces the value with the variant with default values. `None` if the tag is read-only
+    pub set: Option<fn(&mut T, usize)>,
+}
+
+impl<'a, T> Tag<'a, T> {
+    /// Name of the current variant
+    pub fn name(&self) -> &'static str {
+        self.names[(self.index)(self.value)]
+    }
+
+    /// Switches to the variant if it's different from the current one
+    pub fn switch(&mut self, ix: usize) -> bool {
+        match self.set {
+            Some(set) if ix < self.names.len() => {
+                if ix != (self.index)(self.value) {
+                    set(self.value, ix);
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+impl<'a, T> Leaf for Tag<'a, T> {
+    fn to_text(&self) -> String {
+        self.name().to_string()
+    }
+
+    fn set_text(&mut self, text: &str) -> bool {
+        match self.names.iter().position(|name| *name == text) {
+            Some(ix) => self.switch(ix),
+            None => false,
+        }
+    }
+
+    fn reset(&mut self) {
+        self.switch(0);
+    }
+}
+
+impl<'a, T> Inspect for Tag<'a, T> {
+    fn inspect_ctx(&mut self, ctx: &mut InspectCtx, ui: &Ui, label: &str) {
+        crate::leaf(ctx, ui, label, self, |tag| {
+            if tag.set.is_none() {
+                ui.label_text(label, tag.name());
+                return;
+            }
+
+            let mut ix = (tag.index)(tag.value);
+            if ui.combo_simple_string(label, &mut ix, tag.names) {
+                tag.switch(ix);
+            }
+        });
+    }
+
+    fn visit(&mut self, visitor: &mut dyn Visitor) {
+        visitor.leaf(self);
+    }
+}
@@ -0,0 +1,467 @@
+/*!
+Tweakable constants: `igri::tweak!(0.35)`
+
+[`tweak!`](crate::tweak!) registers the value by its source location (file, line and column) on
+the first call and returns the current value on every call. Show the registered tweaks grouped by
+source file with [`window`]:
+
+```ignore
+let speed = igri::tweak!(0.35) * dt;
+// ..
+igri::tweak::window(ui);
+```
+
+`tweak!` takes a literal (numbers and `bool`, optionally negated), so that the value can be written
+back into the source. Tweaked values can be persisted to a sidecar file ([`set_file`]) or written back
+into the source files ([`write_source`]).
+
+With the `dummy` feature, `tweak!` expands to the value.
+*/
+
+use std::{
+    any::Any,
+    cell::RefCell,
+    collections::BTreeMap,
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+use imgui::Ui;
+
+use crate::{
+    text, validate,
+    visit::{self, Entries},
+    Inspect,
+};
+
+thread_local! {
+    static TWEAKS: RefCell<Tweaks> = const {
+        RefCell::new(Tweaks {
+            file: None,
+            map: BTreeMap::new(),
+            saved: BTreeMap::new(),
+            write_error: None,
+        })
+    };
+}
+
+#[derive(Debug)]
+struct Tweaks {
+    /// Sidecar file
+    file: Option<PathBuf>,
+    map: BTreeMap<Location, Tweak>,
+    /// Sidecar key → values in the sidecar file, applied on registration
+    saved: BTreeMap<String, Entries>,
+    /// Error of the last `Write to source` in the window
+    write_error: Option<String>,
+}
+
+/// Source location of a [`tweak!`](crate::tweak!) call
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Location {
+    pub file: &'static str,
+    pub line: u32,
+    /// 1-based column of the macro call
+    pub column: u32,
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}:{}", self.file, self.line, self.column)
+    }
+}
+
+/// Registered value
+struct Tweak {
+    /// Source text of the value
+    source: &'static str,
+    /// Leaf values of the value in the source
+    default: Entries,
+    value: Box<dyn Value>,
+}
+
+impl fmt::Debug for Tweak {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Tweak")
+            .field("source", &self.source)
+            .field("default", &self.default)
+            .finish()
+    }
+}
+
+impl Tweak {
+    fn is_changed(&mut self) -> bool {
+        self.value.entries() != self.default
+    }
+}
+
+/// Type-erased tweak value
+trait Value: Any {
+    fn as_any(&self) -> &dyn Any;
+    fn inspect(&mut self, ui: &Ui, label: &str);
+    fn entries(&mut self) -> Entries;
+    fn apply(&mut self, entries: &[(String, String)]);
+}
+
+impl<T: Inspect + Clone + 'static> Value for T {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn inspect(&mut self, ui: &Ui, label: &str) {
+        Inspect::inspect(self, ui, label);
+    }
+
+    fn entries(&mut self) -> Entries {
+        visit::collect(self)
+    }
+
+    fn apply(&mut self, entries: &[(String, String)]) {
+        visit::apply(self, entries);
+    }
+}
+
+/// Registers the value on the first call and returns the current value. Prefer
+/// [`tweak!`](crate::tweak!)
+pub fn value<T: Inspect + Clone + 'static>(
+    location: Location,
+    source: &'static str,
+    value: T,
+) -> T {
+    TWEAKS.with(|tweaks| {
+        let mut tweaks = tweaks.borrow_mut();
+
+        if let Some(tweak) = tweaks.map.get(&location) {
+            // the type differs if the source is edited and hot-reloaded
+            if let Some(x) = tweak.value.as_any().downcast_ref::<T>() {
+                return x.clone();
+            }
+        }
+
+        let mut x = value;
+        let default = visit::collect(&mut x);
+        if let Some(saved) = tweaks.saved.get(&self::key(location)) {
+            visit::apply(&mut x, saved);
+        }
+
+        let tweak = Tweak {
+            source,
+            default,
+            value: Box::new(x.clone()),
+        };
+        tweaks.map.insert(location, tweak);
+
+        x
+    })
+}
+
+/// Resets all the tweaks to their values in the source
+pub fn reset_all() {
+    TWEAKS.with(|tweaks| {
+        for tweak in tweaks.borrow_mut().map.values_mut() {
+            let default = tweak.default.clone();
+            tweak.value.apply(&default);
+        }
+    });
+}
+
+/// Sets the sidecar file to persist the tweaked values. The values in the file are applied to the
+/// tweaks on their registration, and the file is updated whenever a value is tweaked in the
+/// [`window`]
+///
+/// The file is a text file with a tab-separated entry per line:
+/// `<file> <line> <column> <leaf path> <value>`.
+pub fn set_file(file: Option<impl Into<PathBuf>>) {
+    let file = file.map(Into::into);
+
+    // the file does not exist on the first run
+    let saved = file
+        .as_ref()
+        .and_then(|file| fs::read_to_string(file).ok())
+        .map(|text| self::parse(&text))
+        .unwrap_or_default();
+
+    TWEAKS.with(|tweaks| {
+        let mut tweaks = tweaks.borrow_mut();
+        for (location, tweak) in tweaks.map.iter_mut() {
+            if let Some(entries) = saved.get(&self::key(*location)) {
+                tweak.value.apply(entries);
+            }
+        }
+        tweaks.saved = saved;
+        tweaks.file = file;
+    });
+}
+
+/// Saves the tweaked values to the sidecar file if it's set
+pub fn save() -> io::Result<()> {
+    let (file, text) = TWEAKS.with(|tweaks| {
+        let mut tweaks = tweaks.borrow_mut();
+        let tweaks = &mut *tweaks;
+
+        for (location, tweak) in &mut tweaks.map {
+            if tweak.is_changed() {
+                tweaks
+                    .saved
+                    .insert(self::key(*location), tweak.value.entries());
+            } else {
+                tweaks.saved.remove(&self::key(*location));
+            }
+        }
+
+        (tweaks.file.clone(), self::to_text(&tweaks.saved))
+    });
+
+    match file {
+        Some(file) => fs::write(file, text),
+        None => Ok(()),
+    }
+}
+
+/// Writes the tweaked leaf values back into the source files, relative to the current directory.
+/// Returns the number of the written values
+///
+/// Calls whose source text has changed since the compilation are skipped.
+pub fn write_source() -> io::Result<usize> {
+    // file → (location, source text, new source text)
+    let mut edits = BTreeMap::<&'static str, Vec<(Location, &'static str, String)>>::new();
+
+    TWEAKS.with(|tweaks| {
+        for (location, tweak) in tweaks.borrow_mut().map.iter_mut() {
+            if !tweak.is_changed() {
+                continue;
+            }
+            if let [(rel, text)] = tweak.value.entries().as_slice() {
+                if rel.is_empty() {
+                    let literal = self::literal(tweak.source, text);
+                    edits.entry(location.file).or_default().push((
+                        *location,
+                        tweak.source,
+                        literal,
+                    ));
+                }
+            }
+        }
+    });
+
+    let mut written = Vec::new();
+    for (file, mut edits) in edits {
+        let text = fs::read_to_string(file)?;
+        let mut lines = text.split('\n').map(str::to_string).collect::<Vec<_>>();
+
+        // right to left, so that the columns stay valid
+        edits.sort_by_key(|edit| std::cmp::Reverse(edit.0));
+        for (location, source, literal) in edits {
+            if let Some(line) = lines.get_mut(location.line as usize - 1) {
+                if self::replace_arg(line, location.column, source, &literal) {
+                    written.push(location);
+                }
+            }
+        }
+
+        fs::write(Path::new(file), lines.join("\n"))?;
+    }
+
+    // the source is the new default
+    TWEAKS.with(|tweaks| {
+        let mut tweaks = tweaks.borrow_mut();
+        for location in &written {
+            if let Some(tweak) = tweaks.map.get_mut(location) {
+                tweak.default = tweak.value.entries();
+            }
+        }
+    });
+    self::save()?;
+
+    Ok(written.len())
+}
+
+/// Shows the registered tweaks grouped by source file
+pub fn window(ui: &Ui) {
+    imgui::Window::new("Tweaks")
+        .size([300.0, 200.0], imgui::Condition::FirstUseEver)
+        .build(ui, || {
+            let mut edited = false;
+
+            TWEAKS.with(|tweaks| {
+                let mut tweaks = tweaks.borrow_mut();
+
+                if tweaks.map.is_empty() {
+                    ui.text_disabled("Wrap values with `igri::tweak!(..)`");
+                }
+
+                let mut current_file = None;
+                let mut file_token = None;
+                for (location, tweak) in tweaks.map.iter_mut() {
+                    if current_file != Some(location.file) {
+                        current_file = Some(location.file);
+                        file_token.take();
+                        file_token = imgui::TreeNode::new(location.file)
+                            .default_open(true)
+                            .push(ui);
+                    }
+                    if file_token.is_none() {
+                        continue;
+                    }
+
+                    let _id = ui.push_id(location.to_string().as_str());
+
+                    let changed = tweak.is_changed();
+                    {
+                        let _disabled = ui.begin_disabled(!changed);
+                        if ui.small_button("reset") {
+                            let default = tweak.default.clone();
+                            tweak.value.apply(&default);
+                            edited = true;
+                        }
+                    }
+                    ui.same_line();
+
+                    ui.text(tweak.source);
+
+                    let before = tweak.value.entries();
+                    // the label is the root of the paths, so it can't contain the source (`.`)
+                    let label = format!("{}:{}", location.line, location.column);
+                    tweak.value.inspect(ui, &label);
+                    edited |= tweak.value.entries() != before;
+                }
+            });
+
+            if edited {
+                // the sidecar file is not critical
+                let _ = self::save();
+            }
+
+            ui.separator();
+            if ui.button("Write to source") {
+                let error = self::write_source()
+                    .err()
+                    .map(|err| format!("failed to write to the source: {}", err));
+                TWEAKS.with(|tweaks| tweaks.borrow_mut().write_error = error);
+            }
+            if ui.is_item_hovered() {
+                ui.tooltip_text("Rewrites the tweaked literals in the source files");
+            }
+            ui.same_line();
+            if ui.button("Reset all") {
+                self::reset_all();
+                let _ = self::save();
+            }
+
+            if let Some(err) = TWEAKS.with(|tweaks| tweaks.borrow().write_error.clone()) {
+                validate::type_error(ui, err);
+            }
+        });
+}
+
+/// `<file>\t<line>\t<column>`
+fn key(location: Location) -> String {
+    format!("{}\t{}\t{}", location.file, location.line, location.column)
+}
+
+/// Parses the sidecar file, skipping invalid lines
+fn parse(text: &str) -> BTreeMap<String, Entries> {
+    let mut saved = BTreeMap::<String, Entries>::new();
+
+    for line in text.lines() {
+        let cols = line.splitn(5, '\t').collect::<Vec<_>>();
+        if let [file, line, column, path, value] = cols.as_slice() {
+            if line.parse::<u32>().is_err() || column.parse::<u32>().is_err() {
+                continue;
+            }
+            saved
+                .entry(format!("{}\t{}\t{}", file, line, column))
+                .or_default()
+                .push((path.to_string(), text::unescape(value)));
+        }
+    }
+
+    saved
+}
+
+fn to_text(saved: &BTreeMap<String, Entries>) -> String {
+    let mut text = String::new();
+
+    for (key, entries) in saved {
+        for (path, value) in entries {
+            text.push_str(&format!("{}\t{}\t{}\n", key, path, text::escape(value)));
+        }
+    }
+
+    text
+}
+
+/// Source text of the value, keeping the kind of the literal in the source
+fn literal(source: &str, text: &str) -> String {
+    const SUFFIXES: &[&str] = &[
+        "f32", "f64", "u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64",
+        "i128", "isize",
+    ];
+
+    // negative literals are stringified as `- 1.5` or `-1.5`
+    let is_hex = source
+        .trim_start_matches(|c: char| c == '-' || c.is_whitespace())
+        .starts_with("0x");
+    let suffix = SUFFIXES
+        .iter()
+        .find(|s| source.ends_with(*s) && !is_hex)
+        .copied()
+        .unwrap_or("");
+    let number = &source[..source.len() - suffix.len()];
+
+    let is_exp = !is_hex && number.contains(['e', 'E']);
+    let is_float = number.contains('.') || is_exp || suffix.starts_with('f');
+
+    let mut literal = match text.parse::<f64>() {
+        // keep the exponent notation, e.g. `1e3`
+        Ok(x) if is_exp => format!("{:e}", x),
+        _ => text.to_string(),
+    };
+    if is_float && literal.parse::<f64>().is_ok() && !literal.contains(['.', 'e', 'E']) {
+        literal.push_str(".0");
+    }
+    literal.push_str(suffix);
+
+    literal
+}
+
+/// Replaces the argument of the `tweak!` call at the 1-based column in the line. Returns false if
+/// the argument is not the expected source text
+fn replace_arg(line: &mut String, column: u32, source: &str, literal: &str) -> bool {
+    let start = match line.char_indices().nth(column as usize - 1) {
+        Some((i, _)) => i,
+        None => return false,
+    };
+
+    let open = match line[start..].find('(') {
+        Some(i) => start + i + 1,
+        None => return false,
+    };
+
+    // the matching parenthesis
+    let mut depth = 0;
+    let mut close = None;
+    for (i, c) in line[open..].char_indices() {
+        match c {
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' if depth == 0 => {
+                close = Some(open + i);
+                break;
+            }
+            ')' | ']' | '}' => depth -= 1,
+            _ => {}
+        }
+    }
+    let close = match close {
+        Some(close) => close,
+        None => return false,
+    };
+
+    let strip = |s: &str| s.chars().filter(|c| !c.is_whitespace()).collect::<String>();
+    if strip(&line[open..close]) != strip(source) {
+        return false;
+    }
+
+    line.replace_range(open..close, literal);
+    true
+}
@@ -0,0 +1,102 @@
+/*!
+Validation helpers for `#[inspect(validate = .., min = .., max = ..)]`
+*/
+
+use std::{cell::RefCell, collections::HashMap, fmt};
+
+use imgui::Ui;
+
+use crate::{journal, watchpoint, Inspect, InspectCtx};
+
+/// Color of validation errors
+pub const ERROR_COLOR: [f32; 4] = [1.0, 0.35, 0.35, 1.0];
+
+thread_local! {
+    /// Field path → error message of rejected edits
+    static REJECTED: RefCell<HashMap<String, String>> = RefCell::new(HashMap::new());
+}
+
+/// Shows a validation error beside the last item
+pub fn error(ui: &Ui, err: impl fmt::Display) {
+    ui.same_line();
+    ui.text_colored(ERROR_COLOR, err.to_string());
+}
+
+/// Shows a validation error of the whole value (`#[inspect(validate = ..)]` on types)
+pub fn type_error(ui: &Ui, err: impl fmt::Display) {
+    ui.text_colored(ERROR_COLOR, err.to_string());
+}
+
+/// Clamps the value into `min..=max`. Returns `true` if the value was out of the range
+pub fn clamp<T: PartialOrd>(x: &mut T, min: Option<T>, max: Option<T>) -> bool {
+    if let Some(min) = min {
+        if *x < min {
+            *x = min;
+            return true;
+        }
+    }
+
+    if let Some(max) = max {
+        if *x > max {
+            *x = max;
+            return true;
+        }
+    }
+
+    false
+}
+
+/// Clamps the value into `min..=max` if it was edited through the last item (the field's
+/// widgets, grouped). Returns `true` if the edit was clamped
+///
+/// Values set outside of the inspector are not overwritten; the error is shown beside them instead.
+pub fn clamp_edit<T: PartialOrd>(
+    ctx: &InspectCtx,
+    ui: &Ui,
+    x: &mut T,
+    min: Option<T>,
+    max: Option<T>,
+    msg: &str,
+) -> bool {
+    let out_of_range =
+        min.as_ref().is_some_and(|min| *x < *min) || max.as_ref().is_some_and(|max| *x > *max);
+    let clamped = out_of_range && ui.is_item_edited() && self::clamp(x, min, max);
+
+    let shown = self::reject_edit(ctx, ui, clamped.then_some(msg));
+    if out_of_range && !clamped && !shown {
+        self::error(ui, msg);
+    }
+
+    clamped
+}
+
+/// Notifies watchpoints and the journal of a value changed by validation after an edit
+pub fn commit(ctx: &InspectCtx, x: &mut dyn Inspect) {
+    watchpoint::sync(ctx.path(), x);
+    journal::record_value(ctx.path(), x);
+}
+
+/// Shows the error beside the last item while the last edit of the value at the current path is
+/// rejected. Returns `true` if the error is shown
+///
+/// Rejected edits are reverted on the next frame, so the error is remembered until the item is
+/// deactivated or edited with an accepted value.
+pub fn reject_edit(ctx: &InspectCtx, ui: &Ui, rejected: Option<&str>) -> bool {
+    REJECTED.with(|map| {
+        let mut map = map.borrow_mut();
+
+        if let Some(msg) = rejected {
+            map.insert(ctx.path().to_string(), msg.to_string());
+        } else if ui.is_item_edited() || !ui.is_item_active() {
+            map.remove(ctx.path());
+        }
+
+        match map.get(ctx.path()) {
+            Some(msg) => {
+                self::error(ui, msg);
+                true
+            }
+            None => false,
+        }
+    })
+}
@@ -0,0 +1,180 @@
+/*!
+Visiting the leaves of the inspection tree without drawing
+
+[`Inspect::visit`] lets us copy, paste and reset values generically: every leaf value (numbers,
+strings, enum tags, ..) can be converted to and from text.
+*/
+
+use std::collections::HashMap;
+
+use crate::{
+    path::{FieldPath, Segment},
+    Inspect,
+};
+
+/// Leaf value of the inspection tree, convertible to and from text
+pub trait Leaf {
+    /// Text representation of the value
+    fn to_text(&self) -> String;
+
+    /// Sets the value from the text. Returns `false` if it's invalid or the value is read-only
+    fn set_text(&mut self, text: &str) -> bool;
+
+    /// Resets the value to the default
+    fn reset(&mut self);
+}
+
+/// Visitor of the inspection tree
+pub trait Visitor {
+    /// Enters a child. Returns `false` to skip it (then [`Visitor::exit`] is not called)
+    fn enter(&mut self, segment: Segment) -> bool;
+
+    /// Exits the child
+    fn exit(&mut self);
+
+    /// Label of the child just entered, if it differs from the field name
+    fn label(&mut self, _label: &str) {}
+
+    /// Visits a leaf value
+    fn leaf(&mut self, leaf: &mut dyn Leaf);
+}
+
+/// Standard method to visit a field of a composite value
+pub fn visit_field<T: Inspect + ?Sized>(visitor: &mut dyn Visitor, name: &str, x: &mut T) {
+    if visitor.enter(Segment::Field(name)) {
+        x.visit(visitor);
+        visitor.exit();
+    }
+}
+
+/// Standard method to visit a field with a custom label
+pub fn visit_labeled<T: Inspect + ?Sized>(
+    visitor: &mut dyn Visitor,
+    name: &str,
+    label: &str,
+    x: &mut T,
+) {
+    if visitor.enter(Segment::Field(name)) {
+        visitor.label(label);
+        x.visit(visitor);
+        visitor.exit();
+    }
+}
+
+/// Standard method to visit an element of a sequence
+pub fn visit_index<T: Inspect + ?Sized>(visitor: &mut dyn Visitor, i: usize, x: &mut T) {
+    if visitor.enter(Segment::Index(i)) {
+        x.visit(visitor);
+        visitor.exit();
+    }
+}
+
+/// `(relative path, text)` of each leaf value
+pub type Entries = Vec<(String, String)>;
+
+/// Collects the leaf values of the value
+pub fn collect<T: Inspect + ?Sized>(x: &mut T) -> Entries {
+    let mut collect = Collect::default();
+    x.visit(&mut collect);
+    collect.entries
+}
+
+/// Sets the leaf values of the value. Returns the number of the leaves set
+pub fn apply<T: Inspect + ?Sized>(x: &mut T, entries: &[(String, String)]) -> usize {
+    let mut apply = Apply {
+        path: FieldPath::default(),
+        entries: entries.iter().cloned().collect(),
+        count: 0,
+    };
+    x.visit(&mut apply);
+    apply.count
+}
+
+/// Resets the leaf values of the value to their defaults
+pub fn reset<T: Inspect + ?Sized>(x: &mut T) {
+    x.visit(&mut Reset);
+}
+
+/// If the value is drawn as a single widget rather than a tree node. Values without
+/// [`Inspect::visit`] are not leaves
+pub fn is_leaf<T: Inspect + ?Sized>(x: &mut T) -> bool {
+    let mut is_leaf = IsLeaf(false);
+    x.visit(&mut is_leaf);
+    is_leaf.0
+}
+
+#[derive(Default)]
+struct Collect {
+    path: FieldPath,
+    entries: Entries,
+}
+
+impl Visitor for Collect {
+    fn enter(&mut self, segment: Segment) -> bool {
+        self.path.push(segment);
+        true
+    }
+
+    fn exit(&mut self) {
+        self.path.pop();
+    }
+
+    fn leaf(&mut self, leaf: &mut dyn Leaf) {
+        self.entries
+            .push((self.path.as_str().to_string(), leaf.to_text()));
+    }
+}
+
+struct Apply {
+    path: FieldPath,
+    entries: HashMap<String, String>,
+    count: usize,
+}
+
+impl Visitor for Apply {
+    fn enter(&mut self, segment: Segment) -> bool {
+        self.path.push(segment);
+        true
+    }
+
+    fn exit(&mut self) {
+        self.path.pop();
+    }
+
+    fn leaf(&mut self, leaf: &mut dyn Leaf) {
+        if let Some(text) = self.entries.get(self.path.as_str()) {
+            if leaf.set_text(text) {
+                self.count += 1;
+            }
+        }
+    }
+}
+
+struct Reset;
+
+impl Visitor for Reset {
+    fn enter(&mut self, _segment: Segment) -> bool {
+        true
+    }
+
+    fn exit(&mut self) {}
+
+    fn leaf(&mut self, leaf: &mut dyn Leaf) {
+        leaf.reset();
+    }
+}
+
+/// Visits the root leaf only
+struct IsLeaf(bool);
+
+impl Visitor for IsLeaf {
+    fn enter(&mut self, _segment: Segment) -> bool {
+        false
+    }
+
+    fn exit(&mut self) {}
+
+    fn leaf(&mut self, _leaf: &mut dyn Leaf) {
+        self.0 = true;
+    }
+}
@@ -0,0 +1,271 @@
+/*!
+Watch window: pinned field paths, their latest values and value history plots
+
+Pinned values are updated whenever they're inspected, even if the source node is collapsed. Numeric
+(and `bool`) values keep a ring buffer of recent values, which is plotted in the window.
+
+Pins survive restarts with the rest of the inspector state with [`crate::persist`].
+*/
+
+use std::{cell::RefCell, collections::VecDeque};
+
+use imgui::Ui;
+
+use crate::{
+    path, persist,
+    visit::{self, Leaf},
+    Inspect,
+};
+
+/// Default number of values kept for the plots
+pub const DEFAULT_HISTORY_LEN: usize = 120;
+
+thread_local! {
+    static WATCHES: RefCell<Watches> = RefCell::new(Watches::default());
+}
+
+#[derive(Debug)]
+struct Watches {
+    list: Vec<Watch>,
+    history_len: usize,
+}
+
+impl Default for Watches {
+    fn default() -> Self {
+        Self {
+            list: Vec::new(),
+            history_len: DEFAULT_HISTORY_LEN,
+        }
+    }
+}
+
+impl Watches {
+    fn pins(&self) -> Vec<String> {
+        self.list.iter().map(|w| w.path.clone()).collect()
+    }
+}
+
+/// Pinned field path
+#[derive(Debug, Clone)]
+struct Watch {
+    path: String,
+    /// Plot the history as a histogram instead of lines
+    histogram: bool,
+    /// Leaves under the path, updated on inspection
+    values: Vec<WatchValue>,
+}
+
+impl Watch {
+    fn new(path: &str) -> Self {
+        Self {
+            path: path.to_string(),
+            histogram: false,
+            values: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct WatchValue {
+    path: String,
+    text: String,
+    /// Recent values if it's numeric
+    history: VecDeque<f32>,
+}
+
+/// If the path is watched
+pub fn is_watched(path: &str) -> bool {
+    WATCHES.with(|ws| ws.borrow().list.iter().any(|w| w.path == path))
+}
+
+/// Pins the path to the watch window
+pub fn watch(path: &str) {
+    WATCHES.with(|ws| {
+        let mut ws = ws.borrow_mut();
+        if !ws.list.iter().any(|w| w.path == path) {
+            ws.list.push(Watch::new(path));
+        }
+    });
+    persist::on_change();
+}
+
+/// Unpins the path from the watch window
+pub fn unwatch(path: &str) {
+    WATCHES.with(|ws| ws.borrow_mut().list.retain(|w| w.path != path));
+    persist::on_change();
+}
+
+/// Pinned paths
+pub fn pins() -> Vec<String> {
+    WATCHES.with(|ws| ws.borrow().pins())
+}
+
+/// Recent numeric values of the watched leaf, the oldest first
+pub fn history(leaf_path: &str) -> Vec<f32> {
+    WATCHES.with(|ws| {
+        ws.borrow()
+            .list
+            .iter()
+            .flat_map(|w| &w.values)
+            .find(|v| v.path == leaf_path)
+            .map(|v| v.history.iter().copied().collect())
+            .unwrap_or_default()
+    })
+}
+
+/// Sets the number of values kept for the plots (default: [`DEFAULT_HISTORY_LEN`])
+pub fn set_history_len(len: usize) {
+    WATCHES.with(|ws| ws.borrow_mut().history_len = len);
+}
+
+/// Records the leaf value if it's under a watched path
+pub(crate) fn record(leaf_path: &str, leaf: &dyn Leaf) {
+    let watched = WATCHES.with(|ws| {
+        ws.borrow()
+            .list
+            .iter()
+            .any(|w| path::strip(&w.path, leaf_path).is_some())
+    });
+
+    if watched {
+        self::record_text(leaf_path, leaf.to_text());
+    }
+}
+
+/// Records the leaf values under the node if it's related to any watched path. Inspected values are
+/// recorded automatically; call this to keep the plots running for values that are not inspected
+pub fn sample(node_path: &str, value: &mut dyn Inspect) {
+    let related = WATCHES.with(|ws| {
+        ws.borrow().list.iter().any(|w| {
+            path::strip(&w.path, node_path).is_some() || path::strip(node_path, &w.path).is_some()
+        })
+    });
+
+    if !related {
+        return;
+    }
+
+    for (rel, text) in visit::collect(value) {
+        let leaf_path = path::join(node_path, &rel);
+        self::record_text(&leaf_path, text);
+    }
+}
+
+fn record_text(leaf_path: &str, text: String) {
+    let number = match text.as_str() {
+        "true" => Some(1.0),
+        "false" => Some(0.0),
+        _ => text.parse::<f32>().ok(),
+    };
+
+    WATCHES.with(|ws| {
+        let mut ws = ws.borrow_mut();
+        let history_len = ws.history_len.max(1);
+
+        for w in ws
+            .list
+            .iter_mut()
+            .filter(|w| path::strip(&w.path, leaf_path).is_some())
+        {
+            let ix = match w.values.iter().position(|v| v.path == leaf_path) {
+                Some(ix) => ix,
+                None => {
+                    w.values.push(WatchValue {
+                        path: leaf_path.to_string(),
+                        text: String::new(),
+                        history: VecDeque::with_capacity(history_len),
+                    });
+                    w.values.len() - 1
+                }
+            };
+
+            let value = &mut w.values[ix];
+            value.text = text.clone();
+
+            if let Some(x) = number {
+                while value.history.len() >= history_len {
+                    value.history.pop_front();
+                }
+                value.history.push_back(x);
+            }
+        }
+    });
+}
+
+fn set_histogram(path: &str, histogram: bool) {
+    WATCHES.with(|ws| {
+        let mut ws = ws.borrow_mut();
+        if let Some(w) = ws.list.iter_mut().find(|w| w.path == path) {
+            w.histogram = histogram;
+        }
+    });
+}
+
+/// Shows the watched paths, their latest values and the value history plots
+pub fn window(ui: &Ui) {
+    imgui::Window::new("Watch")
+        .size([300.0, 200.0], imgui::Condition::FirstUseEver)
+        .build(ui, || {
+            let watches = WATCHES.with(|ws| ws.borrow().list.clone());
+
+            if watches.is_empty() {
+                ui.text_disabled("Right click on a field and select `Watch`");
+            }
+
+            for w in &watches {
+                let _id = ui.push_id(w.path.as_str());
+
+                if ui.small_button("x") {
+                    self::unwatch(&w.path);
+                }
+                ui.same_line();
+
+                let mut histogram = w.histogram;
+                if ui.checkbox("##histogram", &mut histogram) {
+                    self::set_histogram(&w.path, histogram);
+                }
+                if ui.is_item_hovered() {
+                    ui.tooltip_text("Plot as a histogram");
+                }
+                ui.same_line();
+
+                ui.text(&w.path);
+
+                if w.values.is_empty() {
+                    ui.text_disabled("(not inspected yet)");
+                }
+
+                for value in &w.values {
+                    let rel = path::strip(&w.path, &value.path).unwrap_or(&value.path);
+                    let text = if rel.is_empty() {
+                        value.text.clone()
+                    } else {
+                        format!("{} = {}", rel, value.text)
+                    };
+
+                    if value.history.is_empty() {
+                        ui.bullet_text(&text);
+                        continue;
+                    }
+
+                    let history = value.history.iter().copied().collect::<Vec<_>>();
+                    let plot_id = format!("##{}", value.path);
+                    let size = [ui.content_region_avail()[0], 40.0];
+
+                    if w.histogram {
+                        ui.plot_histogram(&plot_id, &history)
+                            .overlay_text(&text)
+                            .graph_size(size)
+                            .build();
+                    } else {
+                        ui.plot_lines(&plot_id, &history)
+                            .overlay_text(&text)
+                            .graph_size(size)
+                            .build();
+                    }
+                }
+            }
+        });
+
+    persist::flush_debounced();
+}
@@ -0,0 +1,313 @@
+/*!
+Data watchpoints: log (or break) when a field changes outside the inspector
+
+A watchpoint keeps a copy of the leaf values (as text, see [`crate::visit`]) of the field. Every
+frame the field is inspected, the copy is compared with the current value. On a change that is not
+made through the inspector, the change is logged through the log hook (default: the in-memory log
+shown in [`window`]), the node flashes, and the break hook is called if the watchpoint breaks on
+change.
+*/
+
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, VecDeque},
+    time::{Duration, Instant},
+};
+
+use imgui::Ui;
+
+use crate::{path, visit, Inspect};
+
+/// Color of the changed nodes
+pub const FLASH_COLOR: [f32; 4] = [1.0, 0.5, 0.2, 1.0];
+
+/// How long the changed node flashes
+pub const FLASH_DURATION: Duration = Duration::from_millis(1000);
+
+/// Number of the changes kept in the default log
+pub const LOG_LEN: usize = 100;
+
+type LogHook = Box<dyn FnMut(&Change)>;
+type BreakHook = Box<dyn FnMut(&str)>;
+
+thread_local! {
+    static WATCHPOINTS: RefCell<Vec<Watchpoint>> = const { RefCell::new(Vec::new()) };
+    static LOG_HOOK: RefCell<Option<LogHook>> = const { RefCell::new(None) };
+    static BREAK_HOOK: RefCell<Option<BreakHook>> = const { RefCell::new(None) };
+    /// Recent changes logged without the log hook
+    static LOG: RefCell<VecDeque<Change>> = const { RefCell::new(VecDeque::new()) };
+}
+
+/// Change of a leaf value under a watchpoint
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Change {
+    /// Path of the watchpoint
+    pub watchpoint: String,
+    /// Path of the leaf
+    pub path: String,
+    /// `None` if the leaf is added
+    pub old: Option<String>,
+    /// `None` if the leaf is removed
+    pub new: Option<String>,
+}
+
+#[derive(Debug)]
+struct Watchpoint {
+    path: String,
+    /// Call the break hook on change
+    brk: bool,
+    /// Absolute leaf path → text, `None` until the first inspection
+    snapshot: Option<BTreeMap<String, String>>,
+    flash_until: Option<Instant>,
+}
+
+/// If the path has a watchpoint
+pub fn is_set(path: &str) -> bool {
+    WATCHPOINTS.with(|wps| wps.borrow().iter().any(|wp| wp.path == path))
+}
+
+/// If the watchpoint of the path breaks on change
+pub fn breaks(path: &str) -> bool {
+    WATCHPOINTS.with(|wps| wps.borrow().iter().any(|wp| wp.path == path && wp.brk))
+}
+
+/// Sets a watchpoint to the path. With `brk`, the break hook is called on change
+pub fn set(path: &str, brk: bool) {
+    WATCHPOINTS.with(|wps| {
+        let mut wps = wps.borrow_mut();
+        match wps.iter_mut().find(|wp| wp.path == path) {
+            Some(wp) => wp.brk = brk,
+            None => wps.push(Watchpoint {
+                path: path.to_string(),
+                brk,
+                snapshot: None,
+                flash_until: None,
+            }),
+        }
+    });
+}
+
+/// Removes the watchpoint of the path
+pub fn unset(path: &str) {
+    WATCHPOINTS.with(|wps| wps.borrow_mut().retain(|wp| wp.path != path));
+}
+
+/// Sets the hook called for each changed leaf (default: keep the last [`LOG_LEN`] changes for
+/// [`window`])
+pub fn set_log_hook(hook: impl FnMut(&Change) + 'static) {
+    LOG_HOOK.with(|h| *h.borrow_mut() = Some(Box::new(hook)));
+}
+
+/// Sets the hook called with the watchpoint path when a breaking watchpoint is hit, e.g. to pause
+/// the game
+pub fn set_break_hook(hook: impl FnMut(&str) + 'static) {
+    BREAK_HOOK.with(|h| *h.borrow_mut() = Some(Box::new(hook)));
+}
+
+/// Recent changes logged without the log hook, the oldest first
+pub fn logged() -> Vec<Change> {
+    LOG.with(|log| log.borrow().iter().cloned().collect())
+}
+
+pub fn clear_log() {
+    LOG.with(|log| log.borrow_mut().clear());
+}
+
+/// Shows the recent changes logged without the log hook
+pub fn window(ui: &Ui) {
+    imgui::Window::new("Watchpoint log")
+        .size([300.0, 200.0], imgui::Condition::FirstUseEver)
+        .build(ui, || {
+            let changes = self::logged();
+
+            if changes.is_empty() {
+                ui.text_disabled("Right click on a field and select `Watchpoint`");
+            } else if ui.small_button("Clear") {
+                self::clear_log();
+            }
+
+            // the latest first
+            for change in changes.iter().rev() {
+                ui.text_wrapped(format!(
+                    "{}: {} -> {}",
+                    change.path,
+                    change.old.as_deref().unwrap_or("<none>"),
+                    change.new.as_deref().unwrap_or("<none>"),
+                ));
+            }
+        });
+}
+
+/// If the node of the path is flashing after a change
+pub fn is_flashing(path: &str) -> bool {
+    let now = Instant::now();
+    WATCHPOINTS.with(|wps| {
+        wps.borrow()
+            .iter()
+            .any(|wp| wp.path == path && wp.flash_until.is_some_and(|t| now < t))
+    })
+}
+
+/// If any watchpoint covers the path (the path is at or under a watchpoint)
+pub(crate) fn covers(path: &str) -> bool {
+    WATCHPOINTS.with(|wps| {
+        wps.borrow()
+            .iter()
+            .any(|wp| path::strip(&wp.path, path).is_some())
+    })
+}
+
+/// Compares the watchpoints at or under the path with the value
+///
+/// The standard inspectors call it on inspection. Call it manually to check values that are not
+/// inspected every frame.
+pub fn check(path: &str, value: &mut dyn Inspect) {
+    self::check_node(path, value, true);
+}
+
+/// Compares the watchpoint of the node (and of the descendants if `descendants`) with the value
+pub(crate) fn check_node(node_path: &str, value: &mut dyn Inspect, descendants: bool) {
+    let is_target = |wp: &Watchpoint| {
+        if descendants {
+            path::strip(node_path, &wp.path).is_some()
+        } else {
+            wp.path == node_path
+        }
+    };
+
+    if !WATCHPOINTS.with(|wps| wps.borrow().iter().any(is_target)) {
+        return;
+    }
+
+    let entries = self::absolute_entries(node_path, value);
+    let now = Instant::now();
+    let mut changes = Vec::new();
+    let mut breaks = Vec::new();
+
+    WATCHPOINTS.with(|wps| {
+        for wp in wps.borrow_mut().iter_mut().filter(|wp| is_target(wp)) {
+            let new = entries
+                .iter()
+                .filter(|(p, _)| path::strip(&wp.path, p).is_some())
+                .map(|(p, text)| (p.clone(), text.clone()))
+                .collect::<BTreeMap<_, _>>();
+
+            let old = match wp.snapshot.replace(new) {
+                Some(old) => old,
+                // first inspection
+                None => continue,
+            };
+            let new = wp.snapshot.as_ref().unwrap();
+
+            let n_changes = changes.len();
+            self::diff(&wp.path, &old, new, &mut changes);
+
+            if changes.len() > n_changes {
+                wp.flash_until = Some(now + FLASH_DURATION);
+                if wp.brk {
+                    breaks.push(wp.path.clone());
+                }
+            }
+        }
+    });
+
+    // call the hooks after releasing the borrow, so that they can use this module
+    for change in &changes {
+        self::log(change);
+    }
+
+    for path in &breaks {
+        BREAK_HOOK.with(|h| {
+            if let Some(hook) = h.borrow_mut().as_mut() {
+                hook(path);
+            }
+        });
+    }
+}
+
+/// Updates the watchpoint copies with the value edited through the inspector
+pub(crate) fn sync(path: &str, value: &mut dyn Inspect) {
+    let entries = self::absolute_entries(path, value);
+
+    WATCHPOINTS.with(|wps| {
+        for wp in wps.borrow_mut().iter_mut() {
+            // the deeper one of the edited path and the watchpoint path
+            let deeper = if path::strip(&wp.path, path).is_some() {
+                path
+            } else if path::strip(path, &wp.path).is_some() {
+                wp.path.as_str()
+            } else {
+                continue;
+            };
+
+            let snapshot = match wp.snapshot.as_mut() {
+                Some(snapshot) => snapshot,
+                None => continue,
+            };
+
+            snapshot.retain(|p, _| path::strip(deeper, p).is_none());
+            snapshot.extend(
+                entries
+                    .iter()
+                    .filter(|(p, _)| path::strip(deeper, p).is_some())
+                    .cloned(),
+            );
+        }
+    });
+}
+
+fn absolute_entries(path: &str, value: &mut dyn Inspect) -> Vec<(String, String)> {
+    visit::collect(value)
+        .into_iter()
+        .map(|(rel, text)| (path::join(path, &rel), text))
+        .collect()
+}
+
+fn diff(
+    watchpoint: &str,
+    old: &BTreeMap<String, String>,
+    new: &BTreeMap<String, String>,
+    changes: &mut Vec<Change>,
+) {
+    let mut change = |path: &String, old: Option<&String>, new: Option<&String>| {
+        changes.push(Change {
+            watchpoint: watchpoint.to_string(),
+            path: path.clone(),
+            old: old.cloned(),
+            new: new.cloned(),
+        });
+    };
+
+    for (path, old_text) in old {
+        match new.get(path) {
+            Some(new_text) if new_text == old_text => {}
+            new_text => change(path, Some(old_text), new_text),
+        }
+    }
+
+    for (path, new_text) in new {
+        if !old.contains_key(path) {
+            change(path, None, Some(new_text));
+        }
+    }
+}
+
+fn log(change: &Change) {
+    let logged = LOG_HOOK.with(|h| match h.borrow_mut().as_mut() {
+        Some(hook) => {
+            hook(change);
+            true
+        }
+        None => false,
+    });
+
+    if !logged {
+        LOG.with(|log| {
+            let mut log = log.borrow_mut();
+            while log.len() >= LOG_LEN {
+                log.pop_front();
+            }
+            log.push_back(change.clone());
+        });
+    }
+}
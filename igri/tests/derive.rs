@@ -5,12 +5,22 @@
 //! Run `cargo expand --test derive` to see macro-expaned code (that's why this is an integrated
 //! test).
 
+// the derived types are only compiled, not constructed
+#![allow(dead_code)]
+
 use igri::Inspect;
 
+fn assert_inspect<T: Inspect>() {}
+
 fn f(x: &mut f32, ui: &imgui::Ui, label: &str) {
     ui.label_text(label, format!("{}", x));
 }
 
+/// File in the temp dir, unique to the test process
+fn temp_file(name: &str) -> std::path::PathBuf {
+    std::env::temp_dir().join(format!("igri_{}_{}", std::process::id(), name))
+}
+
 #[test]
 fn derive() {
     #[derive(Inspect)]
@@ -58,4 +68,1224 @@ fn derive() {
         Tuple(f32, u32),
         Unit,
     }
+
+    #[derive(Inspect)]
+    #[inspect(doc_tooltips)]
+    pub struct Labels {
+        #[inspect(label = "Max HP", tooltip = "Maximum hit points")]
+        max_hp: u32,
+        /// Shown as a tooltip
+        #[inspect(with = "f")]
+        documented: f32,
+    }
+
+    #[derive(Inspect)]
+    #[inspect(doc_tooltips)]
+    enum DocVariants {
+        /// Shown on hovering the tag
+        A(u32, #[inspect(skip)] f32, String),
+        #[inspect(tooltip = "Overrides the doc comment")]
+        /// Not shown
+        B,
+    }
+}
+
+#[test]
+fn bounds() {
+    // recursive types don't need manual `bounds`
+    #[derive(Inspect)]
+    pub struct Node {
+        value: u32,
+        children: Vec<Node>,
+    }
+
+    #[derive(Inspect)]
+    pub enum Tree {
+        Leaf(u32),
+        Branch(Vec<Tree>),
+    }
+
+    // only type parameters used in the inspected fields are bounded
+    pub struct NotInspect;
+
+    #[derive(Inspect)]
+    pub struct Generic<T, U> {
+        xs: Vec<T>,
+        #[inspect(skip)]
+        skipped: U,
+        phantom: std::marker::PhantomData<U>,
+    }
+
+    // type parameters only in skipped fields need neither `Inspect` nor `Default`
+    #[derive(Inspect)]
+    pub enum SkippedParam<T, U> {
+        A(u32, #[inspect(skip)] std::marker::PhantomData<T>),
+        B(#[inspect(skip)] Vec<U>),
+    }
+
+    // per-field bound overrides the inferred one
+    #[derive(Inspect)]
+    pub struct FieldBound<T> {
+        #[inspect(bound = "Vec<T>: igri::Inspect")]
+        xs: Vec<T>,
+    }
+
+    // commas inside the bounds
+    #[derive(Inspect)]
+    #[inspect(bounds = "T: igri::Inspect, std::collections::HashMap<T, (T, T)>: Clone,")]
+    pub struct Commas<T> {
+        x: T,
+    }
+
+    // bounds on an enum variant
+    #[derive(Inspect)]
+    pub enum VariantBound<T> {
+        #[inspect(bounds = "T: igri::Inspect + Default")]
+        A(T),
+        B(u32),
+    }
+
+    assert_inspect::<Node>();
+    assert_inspect::<Tree>();
+    assert_inspect::<Generic<u32, NotInspect>>();
+    assert_inspect::<SkippedParam<NotInspect, NotInspect>>();
+    assert_inspect::<FieldBound<u32>>();
+    assert_inspect::<Commas<u32>>();
+    assert_inspect::<VariantBound<u32>>();
+}
+
+#[test]
+fn crate_path() {
+    // `igri` re-exported from another crate
+    mod engine {
+        pub use igri;
+    }
+
+    #[derive(Inspect)]
+    #[inspect(crate = "engine::igri")]
+    pub struct Reexported {
+        x: f32,
+        xs: Vec<u32>,
+    }
+
+    assert_inspect::<Reexported>();
+}
+
+#[test]
+fn layout() {
+    #[derive(Inspect)]
+    pub struct Headers {
+        name: String,
+        #[inspect(group = "Combat")]
+        hp: u32,
+        #[inspect(group = "Items")]
+        items: Vec<u32>,
+        #[inspect(group = "Combat")]
+        atk: u32,
+    }
+
+    #[derive(Inspect)]
+    #[inspect(layout = "tabs")]
+    pub struct Tabs {
+        name: String,
+        #[inspect(group = "Combat")]
+        hp: u32,
+        #[inspect(group = "Items", with = "f")]
+        weight: f32,
+    }
+
+    #[derive(Inspect)]
+    #[inspect(layout = "columns", in_place)]
+    pub struct Columns {
+        name: String,
+        #[inspect(group = "Combat", label = "HP")]
+        hp: u32,
+        nested: Tabs,
+        #[inspect(with = "f")]
+        custom: f32,
+    }
+
+    #[derive(Inspect)]
+    #[inspect(in_place)]
+    pub struct InPlaceHeaders {
+        #[inspect(group = "Combat")]
+        hp: u32,
+    }
+
+    #[derive(Inspect)]
+    pub struct Parent {
+        #[inspect(group = "Combat")]
+        hp: u32,
+        child: InPlaceHeaders,
+    }
+
+    #[derive(Inspect)]
+    pub enum Plain {
+        A,
+        B,
+    }
+
+    // leaves are labelled in the first column, tree nodes by their own header
+    assert!(igri::visit::is_leaf(&mut 0u32));
+    assert!(igri::visit::is_leaf(&mut Plain::A));
+    assert!(!igri::visit::is_leaf(&mut vec![0u32]));
+    assert!(!igri::visit::is_leaf(&mut Tabs {
+        name: String::new(),
+        hp: 0,
+        weight: 0.0,
+    }));
+}
+
+#[test]
+fn conditions() {
+    #[derive(Inspect, PartialEq, Clone, Copy)]
+    pub enum LightKind {
+        Point,
+        Spot,
+    }
+
+    #[derive(Inspect)]
+    #[inspect(layout = "columns")]
+    pub struct Light {
+        kind: LightKind,
+        #[inspect(visible_if = "self.kind == LightKind::Spot")]
+        falloff: f32,
+        enabled: bool,
+        #[inspect(enabled_if = "self.enabled")]
+        intensity: f32,
+    }
+
+    #[derive(Inspect)]
+    pub enum Shape {
+        Circle {
+            filled: bool,
+            // sibling fields are bound by reference in enum variants
+            #[inspect(enabled_if = "*filled")]
+            color: [f32; 3],
+        },
+        Empty,
+    }
+}
+
+fn non_empty(s: &str) -> Result<(), &'static str> {
+    if s.is_empty() {
+        Err("must not be empty")
+    } else {
+        Ok(())
+    }
+}
+
+#[test]
+fn validation() {
+    #[derive(Inspect)]
+    #[inspect(validate = "Range::validate")]
+    pub struct Range {
+        #[inspect(min = 0, max = 100)]
+        min: u32,
+        #[inspect(min = "-1.5")]
+        max: f32,
+        #[inspect(validate = "non_empty")]
+        name: String,
+    }
+
+    impl Range {
+        fn validate(&self) -> Result<(), String> {
+            if self.min as f32 <= self.max {
+                Ok(())
+            } else {
+                Err(format!("min ({}) > max ({})", self.min, self.max))
+            }
+        }
+    }
+
+    #[derive(Inspect)]
+    pub enum Clamped {
+        A {
+            #[inspect(max = 10)]
+            x: i32,
+        },
+        B,
+    }
+
+    #[derive(Inspect)]
+    pub struct Custom {
+        #[inspect(with = "inspect_percent", min = 0, max = 100)]
+        percent: u32,
+    }
+
+    fn inspect_percent(x: &mut u32, ctx: &mut igri::InspectCtx, ui: &imgui::Ui, label: &str) {
+        ui.text(label);
+        x.inspect_ctx(ctx, ui, "##percent");
+    }
+
+    // out-of-range values are clamped
+    let mut range = Range {
+        min: 150,
+        max: -2.0,
+        name: String::new(),
+    };
+    assert!(igri::validate::clamp(&mut range.min, Some(0), Some(100)));
+    assert_eq!(range.min, 100);
+    assert!(igri::validate::clamp(&mut range.max, Some(-1.5), None));
+    assert_eq!(range.max, -1.5);
+    assert!(!igri::validate::clamp(&mut range.min, Some(0), Some(100)));
+    assert_eq!(range.min, 100);
+    assert_eq!(range.validate(), Err("min (100) > max (-1.5)".to_string()));
+    assert_eq!(non_empty(&range.name), Err("must not be empty"));
+
+    // invalid leaf values are rejected
+    let mut non_zero = std::num::NonZeroU32::new(3).unwrap();
+    let zero = [(String::new(), "0".to_string())];
+    assert_eq!(igri::visit::apply(&mut non_zero, &zero), 0);
+    assert_eq!(non_zero.get(), 3);
+
+    // values changed by validation are committed like edits
+    igri::journal::start();
+    range.min = 150;
+    let mut ctx = igri::InspectCtx::new();
+    ctx.root("range", |ctx| {
+        ctx.field("min", |ctx| {
+            if igri::validate::clamp(&mut range.min, Some(0), Some(100)) {
+                igri::validate::commit(ctx, &mut range.min);
+            }
+        })
+    });
+    let journal = igri::journal::stop();
+    assert_eq!(journal.edits.len(), 1);
+    assert_eq!(
+        (
+            journal.edits[0].path.as_str(),
+            journal.edits[0].value.as_str()
+        ),
+        ("range.min", "100")
+    );
+}
+
+#[test]
+fn buttons() {
+    #[derive(Inspect)]
+    #[inspect(button("Reset", "reset"), button("Kill", "kill", confirm))]
+    #[inspect(button("Respawn", "respawn", confirm = "Respawn at the origin?"))]
+    pub struct Actor {
+        hp: u32,
+    }
+
+    impl Actor {
+        fn reset(&mut self) {
+            self.hp = 100;
+        }
+
+        fn kill(&mut self) {
+            self.hp = 0;
+        }
+
+        fn respawn(&mut self) {
+            self.hp = 100;
+        }
+    }
+}
+
+#[test]
+fn visit() {
+    #[derive(Debug, Default, PartialEq, Inspect)]
+    pub enum Shape {
+        #[default]
+        Point,
+        Circle {
+            r: f32,
+        },
+    }
+
+    #[derive(Debug, Default, PartialEq, Inspect)]
+    pub struct Entity {
+        name: String,
+        pos: [f32; 2],
+        shape: Shape,
+        items: Vec<u32>,
+        #[inspect(skip)]
+        id: u32,
+    }
+
+    let mut a = Entity {
+        name: "slime".to_string(),
+        pos: [1.0, 2.0],
+        shape: Shape::Circle { r: 0.5 },
+        items: vec![3],
+        id: 1,
+    };
+
+    let entries = igri::visit::collect(&mut a);
+    assert!(entries.contains(&("shape.tag".to_string(), "Circle".to_string())));
+    assert!(entries.contains(&("items[0]".to_string(), "3".to_string())));
+
+    let mut b = Entity {
+        items: vec![0],
+        id: 1,
+        ..Default::default()
+    };
+    igri::visit::apply(&mut b, &entries);
+    assert_eq!(a, b);
+
+    igri::visit::reset(&mut b);
+    assert_eq!(
+        b,
+        Entity {
+            items: vec![0],
+            id: 1,
+            ..Default::default()
+        }
+    );
+
+    // invalid text (e.g. pasted) is rejected instead of panicking
+    let mut d = std::time::Duration::from_secs(1);
+    for text in ["inf", "1e30", "-1", "NaN", "x"] {
+        assert_eq!(
+            igri::visit::apply(&mut d, &[(String::new(), text.to_string())]),
+            0
+        );
+    }
+    assert_eq!(d, std::time::Duration::from_secs(1));
+    assert_eq!(
+        igri::visit::apply(&mut d, &[(String::new(), "0.5".to_string())]),
+        1
+    );
+    assert_eq!(d, std::time::Duration::from_millis(500));
+}
+
+#[test]
+fn ctx() {
+    use igri::InspectCtx;
+
+    fn with_ctx(x: &mut f32, ctx: &mut InspectCtx, ui: &imgui::Ui, label: &str) {
+        ui.label_text(label, format!("{} at {}", x, ctx.path()));
+    }
+
+    #[derive(Inspect)]
+    pub struct Old {
+        #[inspect(with = "f")]
+        x: f32,
+    }
+
+    fn newtype_with_ctx(x: &mut NewType, ctx: &mut InspectCtx, ui: &imgui::Ui, label: &str) {
+        with_ctx(&mut x.0, ctx, ui, label);
+    }
+
+    #[derive(Inspect)]
+    #[inspect(with = "newtype_with_ctx")]
+    pub struct NewType(f32);
+
+    #[derive(Inspect)]
+    pub struct New {
+        #[inspect(with = "with_ctx")]
+        x: f32,
+    }
+
+    // impls without the context keep compiling
+    pub struct OldImpl(Vec<f32>);
+
+    impl Inspect for OldImpl {
+        fn inspect(&mut self, ui: &imgui::Ui, label: &str) {
+            igri::nest(ui, label, || {
+                igri::seq(self.0.iter_mut(), ui, "xs");
+            });
+        }
+    }
+
+    assert_inspect::<OldImpl>();
+
+    let mut ctx = InspectCtx::new();
+    assert_eq!(ctx.insert(1u32), None);
+    assert_eq!(ctx.insert(2u32), Some(1));
+    assert_eq!(ctx.get::<u32>(), Some(&2));
+
+    ctx.root("root", |ctx| {
+        ctx.field("xs", |ctx| {
+            ctx.index(1, |ctx| assert_eq!(ctx.path(), "root.xs[1]"));
+        });
+        assert_eq!(ctx.depth(), 1);
+    });
+    assert_eq!(ctx.path(), "");
+}
+
+#[test]
+fn filter() {
+    use igri::{filter::Filter, InspectCtx};
+
+    #[derive(Default, Inspect)]
+    pub struct Stats {
+        #[inspect(label = "Health")]
+        hp: u32,
+        atk: u32,
+    }
+
+    #[derive(Default, Inspect)]
+    pub struct Entity {
+        name: String,
+        stats: Stats,
+    }
+
+    let mut entity = Entity {
+        name: "slime".to_string(),
+        ..Default::default()
+    };
+
+    let mut ctx = InspectCtx::new();
+    let mut is_hidden = |filter: Filter| {
+        ctx.set_filter(Some(filter));
+        ctx.root("entity", |ctx| ctx.is_hidden("entity", &mut entity))
+    };
+
+    assert!(!is_hidden(Filter::new("atk")));
+    assert!(!is_hidden(Filter::new("health")));
+    assert!(is_hidden(Filter::new("mp")));
+    assert!(is_hidden(Filter::new("slime")));
+    assert!(!is_hidden(Filter {
+        query: "slime".to_string(),
+        values: true,
+    }));
+
+    // descendants are looked up from the matches found within the root
+    ctx.set_filter(Some(Filter::new("atk")));
+    ctx.root("entity", |ctx| {
+        assert!(!ctx.is_hidden("entity", &mut entity));
+        ctx.field("stats", |ctx| {
+            assert!(!ctx.is_hidden("stats", &mut entity.stats));
+            assert!(ctx.field("hp", |ctx| ctx.is_hidden("Health", &mut entity.stats.hp)));
+            assert!(!ctx.field("atk", |ctx| ctx.is_label_hidden("atk")));
+        });
+        assert!(ctx.field("name", |ctx| ctx.is_hidden("name", &mut entity.name)));
+        assert!(ctx.field("name", |ctx| ctx.is_label_hidden("name")));
+    });
+
+    // the contents of values without `visit` are unknown, so they're shown
+    pub struct Opaque;
+
+    impl Inspect for Opaque {
+        fn inspect(&mut self, _ui: &imgui::Ui, _label: &str) {}
+    }
+
+    ctx.root("opaque", |ctx| {
+        assert!(!ctx.is_hidden("opaque", &mut Opaque))
+    });
+}
+
+#[test]
+fn watch() {
+    use igri::{
+        persist::{self, State},
+        watch,
+    };
+
+    #[derive(Default, Inspect)]
+    pub struct Stats {
+        hp: u32,
+        alive: bool,
+        name: String,
+    }
+
+    let pins = vec!["entities[0].hp".to_string(), "player.stats".to_string()];
+
+    // the pins are persisted with the rest of the inspector state
+    let state_file = temp_file("watch_state_test.txt");
+    let state = State {
+        pins: pins.clone(),
+        ..State::default()
+    };
+    state.write(&state_file).unwrap();
+    persist::set_file(Some(&state_file));
+    assert_eq!(watch::pins(), pins);
+    watch::unwatch("entities[0].hp");
+    persist::flush().unwrap();
+    assert_eq!(State::read(&state_file).unwrap().pins, ["player.stats"]);
+    persist::set_file(None::<&str>);
+    let _ = std::fs::remove_file(&state_file);
+
+    // the history is a ring buffer of the numeric (and `bool`) values
+    watch::set_history_len(3);
+    let mut stats = Stats::default();
+    for hp in 0..5 {
+        stats.hp = hp;
+        stats.alive = hp % 2 == 0;
+        watch::sample("player.stats", &mut stats);
+    }
+    assert_eq!(watch::history("player.stats.hp"), [2.0, 3.0, 4.0]);
+    assert_eq!(watch::history("player.stats.alive"), [1.0, 0.0, 1.0]);
+    assert!(watch::history("player.stats.name").is_empty());
+
+    // values not under the pins are not recorded
+    watch::sample("enemy.stats", &mut stats);
+    assert!(watch::history("enemy.stats.hp").is_empty());
+
+    watch::unwatch("player.stats");
+    assert!(watch::pins().is_empty());
+}
+
+#[test]
+fn watchpoint() {
+    use igri::watchpoint::{self, Change};
+    use std::{cell::RefCell, rc::Rc};
+
+    #[derive(Default, Inspect)]
+    pub struct Stats {
+        hp: u32,
+        atk: u32,
+    }
+
+    // without the log hook, the changes are kept in memory
+    let mut stats = Stats::default();
+    watchpoint::set("stats.atk", false);
+    watchpoint::check("stats", &mut stats);
+    stats.atk = 1;
+    watchpoint::check("stats", &mut stats);
+    assert_eq!(
+        watchpoint::logged(),
+        vec![Change {
+            watchpoint: "stats.atk".to_string(),
+            path: "stats.atk".to_string(),
+            old: Some("0".to_string()),
+            new: Some("1".to_string()),
+        }]
+    );
+    watchpoint::clear_log();
+    assert!(watchpoint::logged().is_empty());
+    watchpoint::unset("stats.atk");
+
+    let changes = Rc::new(RefCell::new(Vec::new()));
+    let breaks = Rc::new(RefCell::new(Vec::new()));
+    {
+        let changes = changes.clone();
+        watchpoint::set_log_hook(move |change| changes.borrow_mut().push(change.clone()));
+        let breaks = breaks.clone();
+        watchpoint::set_break_hook(move |path| breaks.borrow_mut().push(path.to_string()));
+    }
+
+    stats = Stats::default();
+    watchpoint::set("stats.hp", false);
+    assert!(watchpoint::is_set("stats.hp"));
+    assert!(!watchpoint::breaks("stats.hp"));
+
+    // the first check only takes the copy
+    watchpoint::check("stats", &mut stats);
+    stats.hp = 10;
+    stats.atk = 3;
+    watchpoint::check("stats", &mut stats);
+
+    assert_eq!(
+        *changes.borrow(),
+        vec![Change {
+            watchpoint: "stats.hp".to_string(),
+            path: "stats.hp".to_string(),
+            old: Some("0".to_string()),
+            new: Some("10".to_string()),
+        }]
+    );
+    assert!(watchpoint::is_flashing("stats.hp"));
+    assert!(breaks.borrow().is_empty());
+
+    watchpoint::set("stats.hp", true);
+    stats.hp = 0;
+    watchpoint::check("stats", &mut stats);
+    assert_eq!(changes.borrow().len(), 2);
+    assert_eq!(*breaks.borrow(), vec!["stats.hp".to_string()]);
+
+    watchpoint::unset("stats.hp");
+    assert!(!watchpoint::is_set("stats.hp"));
+    stats.hp = 5;
+    watchpoint::check("stats", &mut stats);
+    assert_eq!(changes.borrow().len(), 2);
+}
+
+#[test]
+fn persist() {
+    use igri::{filter::Filter, persist::State};
+
+    let mut state = State::default();
+    state.open.insert("entities[1]".to_string(), true);
+    state.open.insert("entities[1].stats".to_string(), false);
+    state.pins.push("entities[0].hp".to_string());
+    state.filters.insert(
+        "Runtime inspector".to_string(),
+        Filter {
+            query: "hp".to_string(),
+            values: true,
+        },
+    );
+
+    let text = state.to_text();
+    assert!(text.contains("open\tentities[1]\n"));
+    assert_eq!(State::parse(&text), state);
+
+    // invalid lines are skipped
+    assert_eq!(State::parse("unknown\tx\nopen\n"), State::default());
+
+    // tabs and newlines in the fields are escaped
+    let mut state = State::default();
+    state.filters.insert(
+        "a\tb".to_string(),
+        Filter {
+            query: "c\nd\\".to_string(),
+            values: false,
+        },
+    );
+    let text = state.to_text();
+    assert_eq!(text.lines().count(), 1);
+    assert_eq!(State::parse(&text), state);
+}
+
+#[test]
+fn inspect_id() {
+    #[derive(Inspect)]
+    #[inspect(id = "self.id")]
+    pub struct Keyed {
+        id: u32,
+        hp: u32,
+    }
+
+    #[derive(Inspect)]
+    #[inspect(id = "self.name()")]
+    pub enum Named {
+        A,
+        B(u32),
+    }
+
+    impl Named {
+        fn name(&self) -> &'static str {
+            match self {
+                Self::A => "a",
+                Self::B(_) => "b",
+            }
+        }
+    }
+
+    let keyed = Keyed { id: 3, hp: 10 };
+    assert_eq!(keyed.inspect_id(), Some(igri::hash_id(&3u32)));
+    assert_eq!(Box::new(keyed).inspect_id(), Some(igri::hash_id(&3u32)));
+    assert_eq!(Named::B(1).inspect_id(), Some(igri::hash_id("b")));
+    assert_eq!(1u32.inspect_id(), None);
+}
+
+#[test]
+fn keyed() {
+    #[derive(Inspect, Default)]
+    pub struct Entity {
+        name: String,
+        hp: u32,
+    }
+
+    #[derive(Inspect, Default)]
+    pub struct World {
+        #[inspect(key = "name")]
+        entities: Vec<Entity>,
+        #[inspect(key = "0")]
+        pairs: std::collections::VecDeque<(u32, bool)>,
+    }
+
+    // keyed fields are visited as plain sequences
+    let mut world = World {
+        entities: vec![Entity {
+            name: "slime".to_string(),
+            hp: 3,
+        }],
+        pairs: vec![(1, true)].into(),
+    };
+    let entries = igri::visit::collect(&mut world);
+    assert!(entries.contains(&("entities[0].hp".to_string(), "3".to_string())));
+    assert!(entries.contains(&("pairs[0].0".to_string(), "1".to_string())));
+
+    // labelled by the keys with the indices, `##` is not hidden by ImGUI
+    let label = |key: &str, i| {
+        let mut buf = String::new();
+        igri::write_keyed_label(&mut buf, key, i).unwrap();
+        buf
+    };
+    assert_eq!(label("slime", 1), "slime [1]###key");
+    assert_eq!(label("#1 ##boss###", 0), "#1 # #boss# # # [0]###key");
+
+    // scoped by the keys, duplicates are distinguished by their occurrences
+    let (ids, n_duplicates) = igri::keyed_ids(["slime", "bat", "slime"].iter());
+    assert_eq!(ids[0], igri::hash_id(&"slime"));
+    assert_eq!(ids[1], igri::hash_id(&"bat"));
+    assert_ne!(ids[2], ids[0]);
+    assert_eq!(n_duplicates, 1);
+
+    // the IDs don't depend on the order
+    let (reordered, _) = igri::keyed_ids(["bat", "slime"].iter());
+    assert_eq!(reordered, [ids[1], ids[0]]);
+}
+
+#[test]
+fn table() {
+    use igri::table::{Column, Columns};
+
+    fn double(x: &mut u32, ui: &imgui::Ui, label: &str) {
+        x.inspect(ui, label);
+    }
+
+    #[derive(Inspect, Default)]
+    pub struct Entity {
+        name: String,
+        #[inspect(label = "HP")]
+        hp: u32,
+        #[inspect(with = "double")]
+        atk: u32,
+        #[inspect(skip)]
+        _def: u32,
+        // cells apply the field attributes
+        #[inspect(min = 0, max = 99, visible_if = "self.hp > 0", tooltip = "Level")]
+        lvl: u32,
+    }
+
+    #[derive(Inspect, Default)]
+    pub struct World {
+        #[inspect(table)]
+        entities: Vec<Entity>,
+    }
+
+    assert_eq!(
+        Entity::columns(),
+        &[
+            Column {
+                name: "name",
+                label: "name"
+            },
+            Column {
+                name: "hp",
+                label: "HP"
+            },
+            Column {
+                name: "lvl",
+                label: "lvl"
+            },
+        ]
+    );
+
+    let mut entity = Entity {
+        hp: 3,
+        ..Default::default()
+    };
+    let hp = igri::visit::collect(entity.column_mut(1));
+    assert_eq!(hp, vec![(String::new(), "3".to_string())]);
+}
+
+#[test]
+fn multi_select() {
+    #[derive(Inspect, Default)]
+    pub struct World {
+        #[inspect(multi_select)]
+        entities: Vec<(u32, bool)>,
+    }
+
+    let selection = [0, 2].iter().copied().collect();
+    igri::multi::set_selection("world.entities", selection);
+    assert_eq!(
+        igri::multi::selection("world.entities")
+            .into_iter()
+            .collect::<Vec<_>>(),
+        vec![0, 2]
+    );
+    assert!(igri::multi::selection("world").is_empty());
+
+    #[derive(Inspect, Debug, Clone, PartialEq)]
+    pub enum Kind {
+        Melee { range: u32 },
+        Ranged { ammo: u32 },
+    }
+
+    impl Default for Kind {
+        fn default() -> Self {
+            Self::Melee { range: 1 }
+        }
+    }
+
+    #[derive(Inspect, Debug, Clone, Default, PartialEq)]
+    pub struct Entity {
+        hp: u32,
+        name: String,
+        kind: Kind,
+    }
+
+    let mut xs = [
+        Entity {
+            hp: 10,
+            name: "a".to_string(),
+            ..Default::default()
+        },
+        Entity {
+            hp: 20,
+            name: "b".to_string(),
+            ..Default::default()
+        },
+        Entity {
+            hp: 30,
+            name: "a".to_string(),
+            ..Default::default()
+        },
+    ];
+    let selection = [0, 2].iter().copied().collect();
+
+    // only the values differing among the selected elements are mixed
+    let mut rows = xs.iter_mut().collect::<Vec<_>>();
+    let mixed = igri::multi::mixed_paths(&mut rows, &selection);
+    assert_eq!(mixed, ["hp".to_string()].iter().cloned().collect());
+
+    // an edit of the first selected element goes to the other selected elements
+    let before = igri::visit::collect(&mut *rows[0]);
+    rows[0].hp = 15;
+    let changed = igri::multi::propagate(&mut rows, &selection, &before);
+    assert_eq!(changed, vec![("hp".to_string(), "15".to_string())]);
+    assert_eq!((xs[0].hp, xs[1].hp, xs[2].hp), (15, 20, 15));
+
+    // a variant switch is propagated with the new variant's fields
+    let mut rows = xs.iter_mut().collect::<Vec<_>>();
+    let before = igri::visit::collect(&mut *rows[0]);
+    rows[0].kind = Kind::Ranged { ammo: 5 };
+    igri::multi::propagate(&mut rows, &selection, &before);
+    assert_eq!(xs[2].kind, Kind::Ranged { ammo: 5 });
+    assert_eq!(xs[1].kind, Kind::default());
+
+    let mut rows = xs.iter_mut().collect::<Vec<_>>();
+    assert!(igri::multi::mixed_paths(&mut rows, &selection).is_empty());
+}
+
+#[test]
+fn diff() {
+    use igri::diff::{Change, Snapshot};
+
+    #[derive(Inspect, Default)]
+    pub struct Stats {
+        hp: u32,
+        atk: u32,
+        items: Vec<u8>,
+    }
+
+    let mut stats = Stats::default();
+    let checkpoint = Snapshot::take(&mut stats);
+
+    stats.hp = 10;
+    stats.items.push(1);
+
+    assert_eq!(
+        checkpoint.changes_in(&mut stats),
+        vec![
+            Change {
+                path: "hp".to_string(),
+                old: Some("0".to_string()),
+                new: Some("10".to_string()),
+            },
+            Change {
+                path: "items[0]".to_string(),
+                old: None,
+                new: Some("1".to_string()),
+            },
+        ]
+    );
+
+    stats.atk = 5;
+    let restored = checkpoint.restore(&mut stats);
+    assert_eq!(restored, 2);
+    assert_eq!((stats.hp, stats.atk), (0, 0));
+}
+
+#[test]
+fn journal() {
+    use igri::journal::{self, Journal};
+
+    #[derive(Inspect, Default)]
+    pub struct Entity {
+        name: String,
+        hp: u32,
+    }
+
+    #[derive(Inspect, Default)]
+    pub struct World {
+        entities: Vec<Entity>,
+    }
+
+    let text = "0.500\tworld.entities[0].hp\t120\n\
+                1.250\tworld.entities[1].name\tslime\\tking\n\
+                2.000\tother.hp\t1\n\
+                invalid line\n\
+                inf\tworld.x\t1\n\
+                1e300\tworld.x\t1\n\
+                -1\tworld.x\t1\n";
+    let journal = Journal::parse(text);
+    assert_eq!(journal.edits.len(), 3);
+    assert_eq!(journal.edits[1].value, "slime\tking");
+    assert_eq!(Journal::parse(&journal.to_text()), journal);
+
+    let mut odd = journal.clone();
+    odd.edits[0].path = "world.map[\"a\tb\nc\"]".to_string();
+    assert_eq!(Journal::parse(&odd.to_text()), odd);
+
+    let mut world = World {
+        entities: vec![Entity::default(), Entity::default()],
+    };
+    let replay = journal.replay_until(&mut world, "world", std::time::Duration::from_secs(1));
+    assert_eq!(replay.applied, 1);
+    assert!(replay.is_complete());
+    assert_eq!(world.entities[0].hp, 120);
+    assert_eq!(world.entities[1].name, "");
+
+    assert_eq!(journal.replay(&mut world, "world").applied, 2);
+    assert_eq!(world.entities[1].name, "slime\tking");
+
+    // edits of missing elements are reported instead of being dropped silently
+    let mut world = World {
+        entities: vec![Entity::default()],
+    };
+    let replay = journal.replay(&mut world, "world");
+    assert_eq!(replay.applied, 1);
+    assert_eq!(replay.unapplied, vec![journal.edits[1].clone()]);
+    assert!(!replay.is_complete());
+
+    assert!(!journal::is_recording());
+    journal::start();
+    assert!(journal::is_recording());
+    assert_eq!(journal::len(), 0);
+    assert!(journal::stop().edits.is_empty());
+    assert!(!journal::is_recording());
+    assert_eq!(journal::len(), 0);
+}
+
+#[test]
+fn tweak() {
+    use igri::tweak::{self, Location};
+
+    for _ in 0..3 {
+        assert_eq!(igri::tweak!(0.35), 0.35);
+    }
+
+    // tweak a value in a source file through the sidecar file and write it back
+    let source = temp_file("tweak_test.rs");
+    let source_text = "fn speed() -> f32 {\n    igri::tweak!(0.35) * 2.0\n}\n";
+    std::fs::write(&source, source_text).unwrap();
+
+    let file: &'static str = Box::leak(source.to_str().unwrap().to_string().into_boxed_str());
+    let location = Location {
+        file,
+        line: 2,
+        column: 5,
+    };
+    assert_eq!(tweak::value(location, "0.35", 0.35f32), 0.35);
+
+    let sidecar = temp_file("tweak_test.txt");
+    std::fs::write(&sidecar, format!("{}\t2\t5\t\t1\n", file)).unwrap();
+    tweak::set_file(Some(&sidecar));
+    assert_eq!(tweak::value(location, "0.35", 0.35f32), 1.0);
+
+    assert_eq!(tweak::write_source().unwrap(), 1);
+    assert_eq!(
+        std::fs::read_to_string(&source).unwrap(),
+        "fn speed() -> f32 {\n    igri::tweak!(1.0) * 2.0\n}\n"
+    );
+
+    // the written value is the new default
+    assert_eq!(std::fs::read_to_string(&sidecar).unwrap(), "");
+    tweak::set_file(None::<&str>);
+
+    // float-ness and negation are kept
+    assert_eq!(igri::tweak!(-2), -2);
+    let source_text = "let x = igri::tweak!(1e3) + igri::tweak!(- 5.5f32);\n";
+    std::fs::write(&source, source_text).unwrap();
+    let exp = Location {
+        file,
+        line: 1,
+        column: 9,
+    };
+    let neg = Location { column: 30, ..exp };
+    tweak::value(exp, "1e3", 1e3);
+    tweak::value(neg, "- 5.5f32", -5.5f32);
+    std::fs::write(
+        &sidecar,
+        format!("{}\t1\t9\t\t1500\n{}\t1\t30\t\t-2\n", file, file),
+    )
+    .unwrap();
+    tweak::set_file(Some(&sidecar));
+    assert_eq!(tweak::write_source().unwrap(), 2);
+    assert_eq!(
+        std::fs::read_to_string(&source).unwrap(),
+        "let x = igri::tweak!(1.5e3) + igri::tweak!(-2.0f32);\n"
+    );
+    tweak::set_file(None::<&str>);
+    for file in [source, sidecar] {
+        let _ = std::fs::remove_file(file);
+    }
+}
+
+#[test]
+fn inspector() {
+    use std::{cell::RefCell, rc::Rc};
+
+    #[derive(Inspect, Default)]
+    pub struct Settings {
+        volume: f32,
+    }
+
+    let settings = Rc::new(RefCell::new(Settings::default()));
+    let world = Rc::new(RefCell::new(vec![1u32, 2]));
+
+    let mut inspector = igri::Inspector::new("Inspector");
+    inspector.register("settings", settings);
+    {
+        let world = world.clone();
+        inspector.register_with("first", move |f| f(&mut world.borrow_mut()[0]));
+    }
+    // replaces the root with the same name
+    inspector.register_with("first", move |f| f(&mut world.borrow_mut()[1]));
+    assert_eq!(
+        inspector.names().collect::<Vec<_>>(),
+        vec!["settings", "first"]
+    );
+
+    inspector.set_detached("first", true);
+    assert!(inspector.is_detached("first"));
+    assert!(!inspector.is_detached("settings"));
+
+    assert!(inspector.unregister("settings"));
+    assert!(!inspector.unregister("settings"));
+    assert_eq!(inspector.names().collect::<Vec<_>>(), vec!["first"]);
+}
+
+#[test]
+fn inspect_macro() {
+    #[derive(Inspect, Default)]
+    pub struct Player {
+        pos: [f32; 2],
+    }
+
+    // compile check; drawing needs an ImGUI frame
+    #[allow(dead_code)]
+    fn locals(ui: &imgui::Ui) {
+        let mut player = Player::default();
+        let mut speed = 1.0f32;
+        igri::inspect!(ui, player.pos, speed);
+        igri::inspect!(ui, window = "Locals", player, speed,);
+    }
+}
+
+#[test]
+fn registry() {
+    use std::any::Any;
+
+    #[derive(Inspect, Default)]
+    pub struct Enemy {
+        hp: u32,
+    }
+
+    #[derive(Inspect)]
+    pub struct Scene {
+        objects: Vec<Box<dyn Any>>,
+    }
+
+    pub struct Item;
+
+    igri::registry::register::<Enemy>();
+    assert!(igri::registry::is_registered::<Enemy>());
+    assert!(!igri::registry::is_registered::<String>());
+
+    let mut scene = Scene {
+        objects: vec![
+            Box::new(Enemy { hp: 3 }),
+            Box::new("unregistered"),
+            igri::registry::boxed(Item),
+        ],
+    };
+    assert_eq!(igri::registry::type_name(&*scene.objects[0]), Some("Enemy"));
+    assert_eq!(igri::registry::type_name(&*scene.objects[1]), None);
+    assert_eq!(igri::registry::type_name(&*scene.objects[2]), None);
+
+    // unregistered values are labelled with the type names known from `boxed`
+    assert_eq!(scene.objects[0].inspect_type_name(), "Enemy");
+    assert_eq!(scene.objects[1].inspect_type_name(), "<unregistered>");
+    assert_eq!(scene.objects[2].inspect_type_name(), "<unregistered Item>");
+
+    // unregistered values have no leaves
+    let entries = igri::visit::collect(&mut scene);
+    assert_eq!(
+        entries,
+        vec![("objects[0].hp".to_string(), "3".to_string())]
+    );
+
+    let mut any: &mut dyn Any = &mut *scene.objects[0];
+    igri::visit::apply(&mut any, &[("hp".to_string(), "5".to_string())]);
+    assert_eq!(scene.objects[0].downcast_ref::<Enemy>().unwrap().hp, 5);
+
+    igri::registry::unregister::<Enemy>();
+    assert!(!igri::registry::is_registered::<Enemy>());
+    assert_eq!(scene.objects[0].inspect_type_name(), "<unregistered Enemy>");
+
+    // the short name is made once per type
+    igri::registry::register::<Enemy>();
+    let name = igri::registry::type_name(&*scene.objects[0]).unwrap();
+    igri::registry::unregister::<Enemy>();
+    igri::registry::register::<Enemy>();
+    let again = igri::registry::type_name(&*scene.objects[0]).unwrap();
+    assert!(std::ptr::eq(name, again));
+    igri::registry::unregister::<Enemy>();
+}
+
+#[test]
+fn dyn_inspect() {
+    use igri::DynInspect;
+
+    #[derive(Inspect, Default)]
+    pub struct Enemy {
+        hp: u32,
+    }
+
+    #[derive(Inspect, Default)]
+    pub struct Generic<T: Inspect> {
+        x: T,
+    }
+
+    fn assert_object_safe(_: &dyn Inspect) {}
+    assert_object_safe(&Enemy::default());
+
+    assert_eq!(Enemy::default().inspect_type_name(), "Enemy");
+    assert_eq!(0u32.inspect_type_name(), "u32");
+    assert!(Generic::<u8>::default()
+        .inspect_type_name()
+        .ends_with("Generic<u8>"));
+
+    let mut objects: Vec<Box<dyn Inspect>> = vec![Box::new(Enemy { hp: 1 }), Box::new(2u8)];
+    assert_eq!(objects[0].inspect_type_name(), "Enemy");
+    assert_eq!(
+        igri::visit::collect(&mut objects),
+        vec![
+            ("[0].hp".to_string(), "1".to_string()),
+            ("[1]".to_string(), "2".to_string()),
+        ]
+    );
+
+    let mut plugins: Vec<Box<dyn DynInspect>> = vec![Box::new(Enemy { hp: 3 })];
+    assert_eq!(plugins[0].inspect_type_name(), "Enemy");
+    plugins[0].downcast_mut::<Enemy>().unwrap().hp = 4;
+    assert_eq!(plugins[0].downcast_ref::<Enemy>().unwrap().hp, 4);
+    assert!(plugins[0].downcast_ref::<u8>().is_none());
+
+    // `as_any` on the `Box` element refers to the boxed value, not the `Box`
+    assert!(plugins[0].as_any().is::<Enemy>());
+    plugins[0].as_any_mut().downcast_mut::<Enemy>().unwrap().hp = 5;
+    assert_eq!(plugins[0].as_any().downcast_ref::<Enemy>().unwrap().hp, 5);
+
+    // borrowed trait objects
+    #[derive(Inspect)]
+    pub struct Borrowed<'a> {
+        x: &'a mut dyn Inspect,
+        plugin: &'a mut dyn DynInspect,
+    }
+
+    let mut hp = 6u32;
+    let mut borrowed = Borrowed {
+        x: &mut hp,
+        plugin: &mut *plugins[0],
+    };
+    assert_eq!(borrowed.x.inspect_type_name(), "u32");
+    assert_eq!(
+        igri::visit::collect(&mut borrowed),
+        vec![
+            ("x".to_string(), "6".to_string()),
+            ("plugin.hp".to_string(), "5".to_string()),
+        ]
+    );
 }
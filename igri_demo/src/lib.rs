@@ -70,6 +70,10 @@ pub fn run(
                 event: WindowEvent::CloseRequested,
                 ..
             } => {
+                // the inspector state is written lazily
+                if let Err(err) = igri::persist::flush() {
+                    eprintln!("failed to save the inspector state: {}", err);
+                }
                 *control_flow = ControlFlow::Exit;
             }
             event => {
@@ -81,7 +85,8 @@ pub fn run(
 
 fn init_imgui(window: &Window) -> (WinitPlatform, imgui::Context) {
     let mut imgui_context = imgui::Context::create();
-    imgui_context.set_ini_filename(None);
+    // window layout (tree states are saved by `igri::persist`)
+    imgui_context.set_ini_filename(Some(std::path::PathBuf::from("igri_demo.ini")));
 
     // TODO: change on platform
     // imgui_context.io_mut().display_framebuffer_scale = [1.0, 1.0];
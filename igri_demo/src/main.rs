@@ -7,13 +7,18 @@ Don't expect a good getting started tutorial of windowing libraries, graphics AP
 // #![feature(trace_macros)]
 // trace_macros!(true);
 
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+};
+
 use anyhow::*;
 
 use glutin::{
     dpi, event_loop::EventLoop, platform::macos::WindowBuilderExtMacOS, window::WindowBuilder,
 };
 
-use igri::Inspect;
+use igri::{Inspect, InspectCtx};
 
 use igri_demo::ContextWrapper;
 
@@ -21,8 +26,8 @@ use igri_demo::ContextWrapper;
 #[inspect(with = "inspect_newtype")]
 pub struct NewType(u32);
 
-fn inspect_newtype(x: &mut NewType, ui: &imgui::Ui, label: &str) {
-    x.0.inspect(ui, label);
+fn inspect_newtype(x: &mut NewType, ctx: &mut InspectCtx, ui: &imgui::Ui, label: &str) {
+    x.0.inspect_ctx(ctx, ui, label);
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -54,6 +59,8 @@ pub struct AttrDemo {
     newtype: NewType,
     #[inspect(with = "inspect_uninspectable")]
     uninspectable: Uninspectable,
+    /// Check several elements to edit them together
+    #[inspect(multi_select)]
     enums: Vec<AttrDemoEnum>,
 }
 
@@ -62,18 +69,42 @@ fn inspect_f32(x: &mut f32, ui: &imgui::Ui, _label: &str) {
 }
 
 #[derive(Debug, Clone, PartialEq, Inspect)]
+#[inspect(doc_tooltips, layout = "columns")]
+#[inspect(button("Heal", "heal"), button("Kill", "kill", confirm))]
 pub struct Entity {
     pub name: String,
+    /// Hit points. The entity dies when it reaches zero
+    #[inspect(label = "HP", group = "Stats")]
     pub hp: u32,
+    #[inspect(label = "Attack", tooltip = "Base damage", group = "Stats")]
     pub atk: u32,
+    #[inspect(label = "Defense", tooltip = "Damage reduction", group = "Stats")]
     pub def: u32,
     pub ty: EntityType,
 }
 
+impl Entity {
+    fn heal(&mut self) {
+        self.hp += 10;
+    }
+
+    fn kill(&mut self) {
+        self.hp = 0;
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Inspect)]
+#[inspect(doc_tooltips)]
 pub enum EntityType {
-    Player { items: Vec<f32> },
-    Enemy { ai: usize, item: Option<f32> },
+    /// Controlled by the user
+    Player {
+        items: Vec<f32>,
+    },
+    /// Controlled by the AI
+    Enemy {
+        ai: usize,
+        item: Option<f32>,
+    },
     Unknown,
 }
 
@@ -130,7 +161,7 @@ Ooooooooh
         },
     ];
 
-    let mut demo = AttrDemo {
+    let demo = Rc::new(RefCell::new(AttrDemo {
         hidden: 100,
         manual: 200.0,
         newtype: NewType(100),
@@ -144,18 +175,91 @@ Ooooooooh
             AttrDemoEnum::Tuple(0, 10.0, "tuple".to_string()),
             AttrDemoEnum::Unit,
         ],
-    };
+    }));
+
+    // keep the expanded nodes, watched fields and filters over restarts
+    igri::persist::set_file(Some("igri_state.txt"));
+    igri::tweak::set_file(Some("igri_tweaks.txt"));
+
+    let mut checkpoint = igri::diff::Snapshot::take(&mut *demo.borrow_mut());
+
+    // named roots in one window, each can be detached into its own window
+    let mut inspector = igri::Inspector::new("Inspector");
+    inspector.register("attribute demo", demo.clone());
+
+    // right click on `entities[0].hp` and select `Break on change` to pause
+    let paused = Rc::new(Cell::new(false));
+    {
+        let paused = paused.clone();
+        igri::watchpoint::set_break_hook(move |_path| paused.set(true));
+    }
+    let mut outside_changes = false;
 
     igri_demo::run(event_loop, context_wrapper, move |ui| {
         ui.show_demo_window(&mut true);
 
-        imgui::Window::new("Runtime inspector")
-            .size([800.0, 600.0], imgui::Condition::FirstUseEver)
-            // semi-transparent window
-            .bg_alpha(0.5)
+        // change outside the inspector, to try watchpoints
+        if outside_changes && !paused.get() {
+            let step = igri::tweak!(1u32);
+            entities[0].hp = entities[0].hp.wrapping_add(step);
+        }
+
+        ui.checkbox("change entities[0].hp every frame", &mut outside_changes);
+        let mut p = paused.get();
+        if ui.checkbox("paused", &mut p) {
+            paused.set(p);
+        }
+
+        inspector.draw(ui);
+
+        // inspector window with the search filter
+        igri::window(ui, "Runtime inspector", |ctx| {
+            // elements keep their open state when reordered
+            igri::seq_keyed(&mut entities, ctx, ui, "entities", |e: &Entity| &e.name);
+            // spreadsheet view for balance tuning
+            igri::table::table(&mut entities, ctx, ui, "entities (table)");
+        });
+
+        // right click on a field and select `Watch`
+        igri::watch::window(ui);
+
+        // changes logged by watchpoints
+        igri::watchpoint::window(ui);
+
+        // `igri::tweak!` values
+        igri::tweak::window(ui);
+
+        // changes since the checkpoint
+        imgui::Window::new("Diff")
+            .size([400.0, 300.0], imgui::Condition::FirstUseEver)
+            .build(ui, || {
+                let mut demo = demo.borrow_mut();
+                if ui.button("Checkpoint") {
+                    checkpoint = igri::diff::Snapshot::take(&mut *demo);
+                }
+
+                let mut ctx = InspectCtx::new();
+                igri::diff::inspect_diff(&mut *demo, &checkpoint, &mut ctx, ui, "attribute demo");
+
+                let current = igri::diff::Snapshot::take(&mut *demo);
+                igri::diff::compare(ui, "compare", &checkpoint, &current);
+            });
+
+        // record the edits and replay them with `Journal::read(..)?.replay(..)`
+        imgui::Window::new("Journal")
+            .size([200.0, 80.0], imgui::Condition::FirstUseEver)
             .build(ui, || {
-                demo.inspect(ui, "attribute demo");
-                entities.inspect(ui, "entities");
+                if !igri::journal::is_recording() {
+                    if ui.button("Record") {
+                        igri::journal::start();
+                    }
+                } else if ui.button("Stop and save") {
+                    let journal = igri::journal::stop();
+                    if let Err(err) = journal.write("igri_journal.txt".as_ref()) {
+                        eprintln!("failed to save the journal: {}", err);
+                    }
+                }
+                ui.text(format!("{} edits", igri::journal::len()));
             });
     })
 }
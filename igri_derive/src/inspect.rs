@@ -1,17 +1,26 @@
 mod args;
+mod bound;
+mod layout;
 mod utils;
 
 use darling::*;
 use proc_macro2::TokenStream as TokenStream2;
 use quote::*;
 
-use self::utils::{imgui_path, inspect_path};
+use self::utils::inspect_path;
 
 /// Implements `Inspect`
 pub fn impl_inspect(ast: syn::DeriveInput) -> TokenStream2 {
     // The derive input is parsed into `darling` types defined in `args` module.
     // (`darling` lets us parse `#[attribute(..)]` in declartive style, while `syn` does not).
-    let args = args::TypeArgs::from_derive_input(&ast).unwrap();
+    let args = match args::TypeArgs::from_derive_input(&ast) {
+        Ok(args) => args,
+        Err(err) => return err.write_errors(),
+    };
+
+    if let Err(err) = args.validate_fields() {
+        return err.write_errors();
+    }
 
     assert!(
         !(args.with.is_some() && args.as_.is_some()),
@@ -28,98 +37,156 @@ fn inspect_struct(
     ty_args: &args::TypeArgs,
     field_args: &ast::Fields<args::FieldArgs>,
 ) -> TokenStream2 {
-    let imgui = imgui_path();
-    let inspect = inspect_path();
+    let inspect = inspect_path(ty_args);
 
-    let inspect = if let Some(as_) = ty_args.as_.as_ref() {
+    let (inspect, visit) = if let Some(as_) = ty_args.as_.as_ref() {
         // case 1. #[inspect(as = "type")]
-        utils::impl_inspect_as(quote!(self), as_)
+        let ty =
+            syn::parse_str::<syn::Type>(as_).expect("#[inspect(as = ..)] must refer to a type");
+        let visit = quote! {
+            let mut bridge: #ty = (*self).into();
+            #inspect::visit(&mut bridge, visitor);
+            *self = bridge.into();
+        };
+        (
+            utils::impl_inspect_as(ty_args, quote!(self), quote!(label), as_),
+            visit,
+        )
     } else if let Some(with) = ty_args.with.as_ref() {
         // case 2. #[inspect(with = "function")]
-        utils::impl_inspect_with(quote!(self), with)
+        (
+            utils::impl_inspect_with(ty_args, quote!(self), quote!(label), with),
+            quote!(),
+        )
     } else {
         // FIXME: more permissive transparent inspection
         let is_transparent = field_args.style == ast::Style::Tuple
             && field_args.iter().filter(|x| !x.skip).count() == 1;
         if is_transparent {
             // case 3. Transparent inspection
-            quote! {
-                use #inspect;
-                self.0.inspect(ui, label);
-            }
+            let visit = quote! {
+                #inspect::visit(&mut self.0, visitor);
+            };
+            let inspect = quote! {
+                #inspect::inspect_ctx(&mut self.0, ctx, ui, label);
+            };
+            (inspect, visit)
         } else if ty_args.in_place {
             // case 4. Flatten
-            let field_inspectors =
-                utils::field_inspectors(|field| quote! { (&mut self.#field) }, &field_args);
+            let fields =
+                layout::struct_fields(ty_args, |field| quote! { (&mut self.#field) }, field_args);
+            let validation = utils::type_validation(ty_args);
+            let buttons = utils::type_buttons(ty_args);
 
-            quote! {
-                #(#field_inspectors)*
-            }
+            let inspect = quote! {
+                #fields
+                #validation
+                #buttons
+            };
+            (inspect, self::struct_visit(ty_args, field_args))
+        } else if utils::inspected_fields(field_args).next().is_none() {
+            // case 5. unit struct, no field
+            let ty_ident = &ty_args.ident;
+            let inspect = quote! {
+                ui.label_text(label, concat!("<", stringify!(#ty_ident), ">"));
+            };
+            (inspect, quote!())
         } else {
-            // case 5. Nest tree node
-            let mut field_inspectors =
-                utils::field_inspectors(|field| quote! { (&mut self.#field) }, &field_args)
-                    .peekable();
-
-            if field_inspectors.peek().is_none() {
-                // unit struct, no field
-                let ty_ident = &ty_args.ident;
-                quote! {
-                    ui.label_text(label, concat!("<", stringify!(#ty_ident), ">"));
-                }
-            } else {
-                // tuple or named fields
-                let open = ty_args.open;
+            // case 6. Nest tree node (tuple or named fields)
+            let fields =
+                layout::struct_fields(ty_args, |field| quote! { (&mut self.#field) }, field_args);
+            let validation = utils::type_validation(ty_args);
+            let buttons = utils::type_buttons(ty_args);
+
+            let inspect = utils::tree_node(
+                ty_args,
+                ty_args.open,
                 quote! {
-                    let _ = #imgui::TreeNode::new(label)
-                        .flags(
-                            #imgui::TreeNodeFlags::OPEN_ON_ARROW |
-                            #imgui::TreeNodeFlags::OPEN_ON_DOUBLE_CLICK
-                        )
-                        .default_open(#open)
-                        .build(ui, ||
-                               {
-                                   #(#field_inspectors)*
-                               });
-                }
-            }
+                    #fields
+                    #validation
+                    #buttons
+                },
+            );
+            (inspect, self::struct_visit(ty_args, field_args))
         }
     };
 
-    utils::impl_inspect(ty_args, utils::struct_inspect_generics(ty_args), inspect)
+    let inspect_impl = utils::impl_inspect(
+        ty_args,
+        utils::struct_inspect_generics(ty_args),
+        inspect,
+        visit,
+    );
+
+    // column metadata for `#[inspect(table)]` (structs inspected field by field)
+    let has_columns = ty_args.as_.is_none()
+        && ty_args.with.is_none()
+        && utils::inspected_fields(field_args).next().is_some();
+    let columns_impl = if has_columns {
+        utils::impl_columns(ty_args, utils::struct_inspect_generics(ty_args), field_args)
+    } else {
+        quote!()
+    };
+
+    quote! {
+        #inspect_impl
+        #columns_impl
+    }
+}
+
+/// `visit` body of a struct: visit each field
+fn struct_visit(
+    ty_args: &args::TypeArgs,
+    field_args: &ast::Fields<args::FieldArgs>,
+) -> TokenStream2 {
+    let visitors =
+        utils::field_visitors(ty_args, |field| quote! { (&mut self.#field) }, field_args);
+    quote! {
+        #(#visitors)*
+    }
 }
 
 fn inspect_enum(ty_args: &args::TypeArgs, variant_args: &[args::VariantArgs]) -> TokenStream2 {
-    // collect field inspectors
-    let matchers = variant_args.iter().map(|v| {
-        let v_ident = &v.ident;
-
-        match v.fields.style {
-            ast::Style::Struct => {
-                let f_idents = v
-                    .fields
-                    .iter()
-                    .map(|f| {
-                        let ident = &f.ident;
-                        quote!(#ident)
-                    })
-                    .collect::<Vec<_>>();
-
-                let field_inspectors = utils::field_inspectors(|field| field, &v.fields);
+    // collect field inspectors and visitors
+    let (matchers, visit_matchers): (Vec<_>, Vec<_>) = variant_args
+        .iter()
+        .map(|v| {
+            let v_ident = &v.ident;
 
-                quote! {
-                    Self::#v_ident { #(#f_idents),* } => {
-                        #(#field_inspectors)*
-                    }
+            match v.fields.style {
+                ast::Style::Struct => {
+                    let f_idents = v
+                        .fields
+                        .iter()
+                        .map(|f| {
+                            let ident = &f.ident;
+                            quote!(#ident)
+                        })
+                        .collect::<Vec<_>>();
+
+                    let field_inspectors =
+                        utils::field_inspectors(ty_args, |field| field, &v.fields);
+                    let field_visitors = utils::field_visitors(ty_args, |field| field, &v.fields);
+
+                    (
+                        quote! {
+                            Self::#v_ident { #(#f_idents),* } => {
+                                #(#field_inspectors)*
+                            }
+                        },
+                        quote! {
+                            Self::#v_ident { #(#f_idents),* } => {
+                                #(#field_visitors)*
+                            }
+                        },
+                    )
                 }
-            }
-            ast::Style::Tuple => {
-                let f_idents = (0..v.fields.len())
-                    .map(|i| format_ident!("f{}", syn::Index::from(i)))
-                    .collect::<Vec<_>>();
+                ast::Style::Tuple => {
+                    let f_idents = (0..v.fields.len())
+                        .map(|i| format_ident!("f{}", syn::Index::from(i)))
+                        .collect::<Vec<_>>();
 
-                let field_inspectors = utils::field_inspectors(
-                    |field| {
+                    let to_field_mut = |field| {
                         use quote::*;
                         use syn::*;
 
@@ -127,68 +194,88 @@ fn inspect_enum(ty_args: &args::TypeArgs, variant_args: &[args::VariantArgs]) ->
                         let f_ident = format_ident!("f{}", x);
 
                         quote! { #f_ident }
-                    },
-                    &v.fields,
-                );
+                    };
 
-                quote! {
-                    Self::#v_ident(#(#f_idents),*) => {
-                        #(#field_inspectors)*
-                    }
+                    let field_inspectors =
+                        utils::field_inspectors(ty_args, to_field_mut, &v.fields);
+                    let field_visitors = utils::field_visitors(ty_args, to_field_mut, &v.fields);
+
+                    (
+                        quote! {
+                            Self::#v_ident(#(#f_idents),*) => {
+                                #(#field_inspectors)*
+                            }
+                        },
+                        quote! {
+                            Self::#v_ident(#(#f_idents),*) => {
+                                #(#field_visitors)*
+                            }
+                        },
+                    )
                 }
+                ast::Style::Unit => (
+                    quote! {
+                        Self::#v_ident => {}
+                    },
+                    quote! {
+                        Self::#v_ident => {}
+                    },
+                ),
             }
-            ast::Style::Unit => quote! {
-                Self::#v_ident => {}
-            },
-        }
-    });
+        })
+        .unzip();
+
+    let igri = utils::crate_path(ty_args);
+    let tag = utils::enum_tag(ty_args, variant_args);
 
-    let body = if variant_args.iter().all(|v| v.fields.is_empty()) {
+    let (body, visit) = if variant_args.iter().all(|v| v.fields.is_empty()) {
         // 1. plain enum: tag selector only
-        let tag_selector = utils::enum_tag_selector(ty_args, variant_args);
+        let tag_inspector = utils::enum_tag_inspector(ty_args, variant_args, quote!(label));
 
-        quote! {
-            #tag_selector
-        }
-    } else {
-        let imgui = utils::imgui_path();
+        let visit = quote! {
+            visitor.leaf(&mut #tag);
+        };
 
-        if ty_args.no_tag {
-            // 2. no_tag: current tag + field inspectors
-            let current_tag = utils::current_enum_tag(ty_args, variant_args);
+        (tag_inspector, visit)
+    } else {
+        let validation = utils::type_validation(ty_args);
+        let buttons = utils::type_buttons(ty_args);
 
-            quote! {
-                if let Some(()) = #imgui::TreeNode::new(label)
-                    .opened(true, #imgui::Condition::FirstUseEver)
-                    .flags(#imgui::TreeNodeFlags::OPEN_ON_ARROW | #imgui::TreeNodeFlags::OPEN_ON_DOUBLE_CLICK)
-                    .build(ui, || {
-                        #current_tag
-
-                        match self {
-                            #(#matchers,)*
-                        }
-                    })
-                {}
-            }
+        // 2. no_tag: current tag + field inspectors
+        // 3. default: tag selector + field inspectors
+        let tag_label = if ty_args.no_tag {
+            quote!(label)
         } else {
-            // 3. default: tag selector + field inspectors
-            let tag_selector = utils::enum_tag_selector(ty_args, variant_args);
+            quote!("tag")
+        };
+        let tag_inspector = utils::enum_tag_inspector(ty_args, variant_args, tag_label);
+        let tag_inspector = utils::field_path(ty_args, "tag", tag_inspector);
 
+        let body = utils::tree_node(
+            ty_args,
+            true,
             quote! {
-                if let Some(()) = #imgui::TreeNode::new(label)
-                    .opened(true, #imgui::Condition::FirstUseEver)
-                    .flags(#imgui::TreeNodeFlags::OPEN_ON_ARROW | #imgui::TreeNodeFlags::OPEN_ON_DOUBLE_CLICK)
-                    .build(ui, || {
-                        #tag_selector
-
-                        match self {
-                            #(#matchers,)*
-                        }
-                    })
-                {}
+                #tag_inspector
+
+                match self {
+                    #(#matchers,)*
+                }
+
+                #validation
+                #buttons
+            },
+        );
+
+        let visit = quote! {
+            #igri::visit::visit_field(visitor, "tag", &mut #tag);
+
+            match self {
+                #(#visit_matchers,)*
             }
-        }
+        };
+
+        (body, visit)
     };
 
-    utils::impl_inspect(ty_args, utils::enum_inspect_generics(ty_args), body)
+    utils::impl_inspect(ty_args, utils::enum_inspect_generics(ty_args), body, visit)
 }
@@ -26,17 +26,36 @@ pub struct TypeArgs {
     pub open: bool,
     /// Add manual type boundary
     #[darling(default)]
-    pub bounds: Option<String>,
+    pub bounds: Option<Bounds>,
     /// (enum only) no tag selector, field inspectors only
     #[darling(default)]
     pub no_tag: bool,
+    /// (struct only) How to lay out the fields and groups
+    #[darling(default)]
+    pub layout: Option<Layout>,
+    /// Show doc comments of fields and variants as tooltips
+    #[darling(default)]
+    pub doc_tooltips: bool,
+    /// Validate the whole value with `fn(&Self) -> Result<(), impl Display>`
+    #[darling(default)]
+    pub validate: Option<ExprPath>,
+    /// Buttons calling `&mut self` methods
+    #[darling(multiple, rename = "button")]
+    pub buttons: Vec<Button>,
+    /// (sequence elements) Stable ImGUI ID from a hashable expression of `self`, e.g. `"self.id"`
+    #[darling(default)]
+    pub id: Option<Expr>,
+    /// Path to the `igri` crate (default: resolved from `Cargo.toml`)
+    #[darling(default, rename = "crate")]
+    pub crate_: Option<Path>,
 }
 
 #[derive(FromField, Clone)]
-#[darling(attributes(inspect))]
+#[darling(attributes(inspect), forward_attrs(doc))]
 pub struct FieldArgs {
     pub ident: Option<Ident>,
     pub ty: Type,
+    pub attrs: Vec<Attribute>,
     // ---
     /// `#[inspect(skip)]`
     ///
@@ -53,16 +72,236 @@ pub struct FieldArgs {
     /// Casts the target before inspection
     #[darling(default)]
     pub with: Option<String>,
+    /// `#[inspect(bound = "<bounds>")]`
+    ///
+    /// Override the inferred `where` boundary for this field
+    #[darling(default)]
+    pub bound: Option<Bounds>,
+    /// `#[inspect(label = "<label>")]`
+    ///
+    /// Label of the field (default: field name or index)
+    #[darling(default)]
+    pub label: Option<String>,
+    /// `#[inspect(tooltip = "<text>")]`
+    ///
+    /// Tooltip on hovering the field
+    #[darling(default)]
+    pub tooltip: Option<String>,
+    /// `#[inspect(group = "<group>")]`
+    ///
+    /// (struct only) Group of the field, shown with the type `layout`
+    #[darling(default)]
+    pub group: Option<String>,
+    /// `#[inspect(visible_if = "<expr>")]`
+    ///
+    /// Show the field only if the expression is true
+    #[darling(default)]
+    pub visible_if: Option<Expr>,
+    /// `#[inspect(enabled_if = "<expr>")]`
+    ///
+    /// Disable the field unless the expression is true
+    #[darling(default)]
+    pub enabled_if: Option<Expr>,
+    /// `#[inspect(validate = "<path>")]`
+    ///
+    /// Validate the field with `fn(&Field) -> Result<(), impl Display>`
+    #[darling(default)]
+    pub validate: Option<ExprPath>,
+    /// `#[inspect(min = <value>)]`
+    ///
+    /// Clamp the field
+    #[darling(default)]
+    pub min: Option<LitExpr>,
+    /// `#[inspect(max = <value>)]`
+    ///
+    /// Clamp the field
+    #[darling(default)]
+    pub max: Option<LitExpr>,
+    /// `#[inspect(key = "<field>")]`
+    ///
+    /// (sequence field) Label and scope the elements by the field of the elements
+    #[darling(default)]
+    pub key: Option<KeyField>,
+    /// `#[inspect(table)]`
+    ///
+    /// (sequence field) Show the elements as a table, a column per field
+    #[darling(default)]
+    pub table: bool,
+    /// `#[inspect(multi_select)]`
+    ///
+    /// (sequence field) Select elements and edit them together
+    #[darling(default)]
+    pub multi_select: bool,
 }
 
 #[derive(FromVariant)]
-#[darling(attributes(inspect))]
+#[darling(attributes(inspect), forward_attrs(doc))]
 pub struct VariantArgs {
     pub ident: Ident,
     pub fields: ast::Fields<FieldArgs>,
+    pub attrs: Vec<Attribute>,
+    /// Tooltip on hovering the tag
+    #[darling(default)]
+    pub tooltip: Option<String>,
+    /// Override the inferred `where` boundary for the variant fields
+    #[darling(default)]
+    pub bounds: Option<Bounds>,
+}
+
+/// `#[inspect(layout = "..")]`
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Layout {
+    /// Collapsing header per group
+    Headers,
+    /// Tab bar with a tab item per group
+    Tabs,
+    /// Two-column label/value table
+    Columns,
+}
+
+impl FromMeta for Layout {
+    fn from_string(value: &str) -> darling::Result<Self> {
+        match value {
+            "headers" => Ok(Self::Headers),
+            "tabs" => Ok(Self::Tabs),
+            "columns" => Ok(Self::Columns),
+            _ => Err(darling::Error::unknown_value(value)),
+        }
+    }
+}
+
+/// `#[inspect(button("<label>", "<method>"))]`, optionally with `confirm` or `confirm = "<message>"`
+#[derive(Clone)]
+pub struct Button {
+    pub label: String,
+    pub method: Ident,
+    pub confirm: Option<String>,
+}
+
+impl FromMeta for Button {
+    fn from_list(items: &[NestedMeta]) -> darling::Result<Self> {
+        let (label, method) = match items {
+            [NestedMeta::Lit(Lit::Str(label)), NestedMeta::Lit(Lit::Str(method)), ..] => (
+                label.value(),
+                method
+                    .parse::<Ident>()
+                    .map_err(|err| darling::Error::custom(err).with_span(method))?,
+            ),
+            _ => {
+                return Err(darling::Error::custom(
+                    "expected `button(\"<label>\", \"<method>\")`",
+                ))
+            }
+        };
+
+        let confirm = match &items[2..] {
+            [] => None,
+            [NestedMeta::Meta(Meta::Path(path))] if path.is_ident("confirm") => {
+                Some("Are you sure?".to_string())
+            }
+            [NestedMeta::Meta(Meta::NameValue(MetaNameValue {
+                path,
+                lit: Lit::Str(msg),
+                ..
+            }))] if path.is_ident("confirm") => Some(msg.value()),
+            [item, ..] => {
+                return Err(
+                    darling::Error::custom("expected `confirm` or `confirm = \"..\"`")
+                        .with_span(item),
+                )
+            }
+        };
+
+        Ok(Self {
+            label,
+            method,
+            confirm,
+        })
+    }
+}
+
+/// Literal or an expression in a string literal, e.g. `0`, `1.5` or `"-1"`
+#[derive(Clone)]
+pub struct LitExpr(pub Expr);
+
+impl FromMeta for LitExpr {
+    fn from_value(value: &Lit) -> darling::Result<Self> {
+        match value {
+            Lit::Str(lit) => lit
+                .parse::<Expr>()
+                .map(Self)
+                .map_err(|err| darling::Error::custom(err).with_span(lit)),
+            lit => Ok(Self(Expr::Lit(ExprLit {
+                attrs: Vec::new(),
+                lit: lit.clone(),
+            }))),
+        }
+    }
+}
+
+/// Field of the sequence elements in a string literal, e.g. `"name"` or `"0"`
+#[derive(Clone)]
+pub struct KeyField(pub Member);
+
+impl FromMeta for KeyField {
+    fn from_value(value: &Lit) -> darling::Result<Self> {
+        match value {
+            Lit::Str(lit) => lit.parse::<Member>().map(Self).map_err(|_| {
+                darling::Error::custom("expected a field name or a tuple index").with_span(lit)
+            }),
+            _ => Err(darling::Error::unexpected_lit_type(value)),
+        }
+    }
+}
+
+/// `where` predicates in a string literal, e.g. `"T: Into<(u8, u8)>, HashMap<K, V>: Inspect"`
+#[derive(Clone)]
+pub struct Bounds(pub Vec<WherePredicate>);
+
+impl FromMeta for Bounds {
+    fn from_value(value: &Lit) -> darling::Result<Self> {
+        let lit = match value {
+            Lit::Str(lit) => lit,
+            _ => return Err(darling::Error::unexpected_lit_type(value)),
+        };
+
+        // parse as a whole so that commas in generic arguments are not treated as separators
+        let predicates = lit
+            .parse_with(punctuated::Punctuated::<WherePredicate, Token![,]>::parse_terminated)
+            .map_err(|err| {
+                darling::Error::custom(format!("invalid bounds: {}", err)).with_span(lit)
+            })?;
+
+        Ok(Self(predicates.into_iter().collect()))
+    }
 }
 
 impl TypeArgs {
+    /// Enumerates the fields of a struct or enum variants, except for those of variants with
+    /// manual `bounds`
+    pub fn inferred_fields(&self) -> Vec<self::FieldArgs> {
+        match &self.data {
+            ast::Data::Struct(field_args) => field_args.fields.clone(),
+            ast::Data::Enum(variants) => variants
+                .iter()
+                .filter(|variant| variant.bounds.is_none())
+                .flat_map(|variant| variant.fields.clone().into_iter())
+                .collect::<Vec<_>>(),
+        }
+    }
+
+    /// Enumerates manual `bounds` of enum variants
+    pub fn variant_bounds(&self) -> Vec<WherePredicate> {
+        match &self.data {
+            ast::Data::Struct(_) => Vec::new(),
+            ast::Data::Enum(variants) => variants
+                .iter()
+                .filter_map(|variant| variant.bounds.as_ref())
+                .flat_map(|bounds| bounds.0.iter().cloned())
+                .collect(),
+        }
+    }
+
     /// Enumerates all the fields of a struct or enum variants
     pub fn all_fields(&self) -> Vec<self::FieldArgs> {
         match &self.data {
@@ -73,4 +312,38 @@ impl TypeArgs {
                 .collect::<Vec<_>>(),
         }
     }
+
+    /// Rejects field attributes that can't be used together or on the type
+    pub fn validate_fields(&self) -> darling::Result<()> {
+        let mut errors = Vec::new();
+
+        for field in self.all_fields() {
+            if self.data.is_enum() && field.group.is_some() {
+                let msg = "#[inspect(group = ..)] is not supported on enum variant fields";
+                errors.push(darling::Error::custom(msg).with_span(&field.ty));
+            }
+
+            let views = [
+                ("table", field.table),
+                ("multi_select", field.multi_select),
+                ("key", field.key.is_some()),
+            ];
+            let views = views
+                .iter()
+                .filter(|(_, used)| *used)
+                .map(|(name, _)| *name)
+                .collect::<Vec<_>>();
+
+            if views.len() > 1 {
+                let msg = format!("#[inspect({})] can't be used together", views.join(", "));
+                errors.push(darling::Error::custom(msg).with_span(&field.ty));
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(darling::Error::multiple(errors))
+        }
+    }
 }
@@ -0,0 +1,144 @@
+/*!
+`where` clause inference for the `Inspect` impl
+
+Like `serde`, we only bound type parameters that actually appear in the field types. Bounding the
+field types themselves (`Vec<Node>: Inspect`) would create unprovable clauses for recursive types and
+leak private field types into the public `where` clause.
+*/
+
+use std::collections::HashSet;
+
+use proc_macro2::TokenStream as TokenStream2;
+use syn::{visit::Visit, *};
+
+use crate::inspect::args;
+
+/// Adds `#[inspect(bound = "..")]` of each field to the `where` clause
+pub fn with_field_bounds(generics: &Generics, fields: &[args::FieldArgs]) -> Generics {
+    let predicates = fields
+        .iter()
+        .filter_map(|f| f.bound.as_ref())
+        .flat_map(|b| b.0.iter().cloned())
+        .collect::<Vec<_>>();
+
+    self::with_predicates(generics, predicates)
+}
+
+/// Adds the predicates to the `where` clause
+pub fn with_predicates(
+    generics: &Generics,
+    predicates: impl IntoIterator<Item = WherePredicate>,
+) -> Generics {
+    let mut generics = generics.clone();
+    generics.make_where_clause().predicates.extend(predicates);
+    generics
+}
+
+/// Adds `T: <bound>` for each type parameter `T` that appears in the filtered fields
+pub fn with_bound(
+    generics: &Generics,
+    fields: &[args::FieldArgs],
+    filter: impl Fn(&args::FieldArgs) -> bool,
+    bound: TokenStream2,
+) -> Generics {
+    let params = generics
+        .type_params()
+        .map(|p| p.ident.clone())
+        .collect::<HashSet<_>>();
+
+    let mut visitor = TypeParamVisitor {
+        params: &params,
+        bounded: Vec::new(),
+    };
+
+    for f in fields.iter().filter(|f| f.bound.is_none() && filter(f)) {
+        visitor.visit_type(&f.ty);
+    }
+
+    let predicates = visitor
+        .bounded
+        .into_iter()
+        .map::<WherePredicate, _>(|ty| parse_quote! { #ty: #bound });
+
+    self::with_predicates(generics, predicates)
+}
+
+/// Adds `FieldType: <bound>` for each filtered field whose type mentions a type parameter
+///
+/// For fields that are only constructed (e.g. skipped fields with `Default::default()`), so that
+/// `PhantomData<T>` or `Vec<T>` don't require `T: <bound>`.
+pub fn with_field_type_bound(
+    generics: &Generics,
+    fields: &[args::FieldArgs],
+    filter: impl Fn(&args::FieldArgs) -> bool,
+    bound: TokenStream2,
+) -> Generics {
+    let params = generics
+        .type_params()
+        .map(|p| p.ident.clone())
+        .collect::<HashSet<_>>();
+
+    let predicates = fields
+        .iter()
+        .filter(|f| f.bound.is_none() && filter(f))
+        .filter(|f| {
+            let mut visitor = TypeParamVisitor {
+                params: &params,
+                bounded: Vec::new(),
+            };
+            visitor.visit_type(&f.ty);
+            !visitor.bounded.is_empty()
+        })
+        .map::<WherePredicate, _>(|f| {
+            let ty = &f.ty;
+            parse_quote! { #ty: #bound }
+        })
+        .collect::<Vec<_>>();
+
+    self::with_predicates(generics, predicates)
+}
+
+/// Collects type parameters (or their associated types) used in a type
+struct TypeParamVisitor<'a> {
+    params: &'a HashSet<Ident>,
+    /// `T` or `T::Assoc`, in the order of appearance
+    bounded: Vec<TypePath>,
+}
+
+impl<'a, 'ast> Visit<'ast> for TypeParamVisitor<'a> {
+    fn visit_type_path(&mut self, ty: &'ast TypePath) {
+        // `PhantomData<T>` is inspectable regardless of `T`
+        if ty.qself.is_none() && is_phantom_data(&ty.path) {
+            return;
+        }
+
+        if ty.qself.is_none() && ty.path.leading_colon.is_none() {
+            let first = &ty.path.segments[0];
+            if self.params.contains(&first.ident) {
+                let bounded = if ty.path.segments.len() == 1 {
+                    // `T`
+                    TypePath {
+                        qself: None,
+                        path: first.ident.clone().into(),
+                    }
+                } else {
+                    // `T::Assoc`
+                    ty.clone()
+                };
+
+                if !self.bounded.contains(&bounded) {
+                    self.bounded.push(bounded);
+                }
+            }
+        }
+
+        visit::visit_type_path(self, ty);
+    }
+
+    // type macros are opaque to us
+    fn visit_type_macro(&mut self, _mac: &'ast TypeMacro) {}
+}
+
+fn is_phantom_data(path: &Path) -> bool {
+    matches!(path.segments.last(), Some(seg) if seg.ident == "PhantomData")
+}
@@ -0,0 +1,155 @@
+/*!
+Field layout: `#[inspect(group = "..")]` on fields and `#[inspect(layout = "..")]` on types
+*/
+
+use darling::*;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::*;
+
+use crate::inspect::{
+    args::{self, Layout},
+    utils,
+};
+
+/// Inspectors of the struct fields, grouped and laid out
+pub fn struct_fields<T: ToTokens>(
+    ty_args: &args::TypeArgs,
+    mut to_field_mut: impl FnMut(TokenStream2) -> T,
+    field_args: &ast::Fields<args::FieldArgs>,
+) -> TokenStream2 {
+    let igri = utils::crate_path(ty_args);
+    let imgui = utils::imgui_path(ty_args);
+    let layout = ty_args.layout.unwrap_or(Layout::Headers);
+
+    // (group, field inspectors) in the order of appearance
+    let mut ungrouped = Vec::new();
+    let mut groups: Vec<(String, Vec<TokenStream2>)> = Vec::new();
+
+    for (field_ident, label, field) in utils::inspected_fields(field_args) {
+        let segment = utils::field_segment(&field_ident);
+        let field_mut = to_field_mut(field_ident);
+        let field_mut = quote!(#field_mut);
+
+        let inspector = if layout == Layout::Columns {
+            // the label of leaf values is shown in the first column, tree nodes keep their own
+            let id = format!("##{}", label);
+            let inspector =
+                utils::field_inspector(ty_args, field, field_mut.clone(), quote!(field_label));
+            let inspector = utils::field_path(ty_args, &segment, inspector);
+            let is_leaf = if field.with.is_none() && field.as_.is_none() {
+                quote!(#igri::visit::is_leaf(#field_mut))
+            } else {
+                quote!(true)
+            };
+            let row = quote! {
+                let is_leaf = #is_leaf;
+                let field_label = if is_leaf { #id } else { #label };
+                ui.table_next_row();
+                ui.table_next_column();
+                if is_leaf {
+                    ui.text(#label);
+                }
+                ui.table_next_column();
+                {
+                    let _width = ui.push_item_width(-f32::MIN_POSITIVE);
+                    #inspector
+                }
+            };
+
+            // skip the whole row if the search filter hides the field
+            let is_hidden = match (field.with.as_ref(), field.as_.as_ref()) {
+                (None, None) => Some(quote!(ctx.is_hidden(#label, #field_mut))),
+                (Some(with), _) => utils::with_path(with)
+                    .map(|with| quote!(#igri::is_with_hidden(&#with, &*#field_mut, ctx, #label))),
+                (None, Some(_)) => None,
+            };
+
+            match is_hidden {
+                Some(is_hidden) => quote! {
+                    if !ctx.field(#segment, |ctx| #is_hidden) {
+                        #row
+                    }
+                },
+                None => row,
+            }
+        } else {
+            let inspector = utils::field_inspector(ty_args, field, field_mut, quote!(#label));
+            utils::field_path(ty_args, &segment, inspector)
+        };
+        let inspector = utils::field_conditions(field, inspector);
+
+        match field.group.as_ref() {
+            None => ungrouped.push(inspector),
+            Some(group) => match groups.iter_mut().find(|(g, _)| g == group) {
+                Some((_, inspectors)) => inspectors.push(inspector),
+                None => groups.push((group.clone(), vec![inspector])),
+            },
+        }
+    }
+
+    let group_names = groups.iter().map(|(g, _)| g).collect::<Vec<_>>();
+    // scoped by the group names so that sibling group sets don't share the IDs
+    let scope = group_names
+        .iter()
+        .map(|g| g.as_str())
+        .collect::<Vec<_>>()
+        .join(",");
+    let tab_bar_id = format!("##groups:{}", scope);
+    let table_id = format!("##fields:{}", scope);
+    let group_inspectors = groups
+        .iter()
+        .map(|(_, inspectors)| quote!(#(#inspectors)*))
+        .collect::<Vec<_>>();
+
+    let fields = match layout {
+        Layout::Headers => quote! {
+            #(#ungrouped)*
+            #(
+                if ui.collapsing_header(#group_names, #imgui::TreeNodeFlags::empty()) {
+                    #group_inspectors
+                }
+            )*
+        },
+        Layout::Tabs if groups.is_empty() => quote! {
+            #(#ungrouped)*
+        },
+        Layout::Tabs => quote! {
+            #(#ungrouped)*
+            if let Some(_tab_bar) = ui.tab_bar(#tab_bar_id) {
+                #(
+                    if let Some(_tab) = ui.tab_item(#group_names) {
+                        #group_inspectors
+                    }
+                )*
+            }
+        },
+        Layout::Columns => quote! {
+            if let Some(_table) = ui.begin_table_with_flags(
+                #table_id,
+                2,
+                #imgui::TableFlags::RESIZABLE | #imgui::TableFlags::BORDERS_INNER_V,
+            ) {
+                #(#ungrouped)*
+                #(
+                    ui.table_next_row();
+                    ui.table_next_column();
+                    ui.text_disabled(#group_names);
+                    #group_inspectors
+                )*
+            }
+        },
+    };
+
+    if ty_args.in_place && !groups.is_empty() {
+        // without a tree node of its own, scope the group IDs and indent the fields so that the
+        // groups don't mix with the parent's
+        quote! {
+            let _id = ui.push_id(label);
+            ui.indent();
+            #fields
+            ui.unindent();
+        }
+    } else {
+        fields
+    }
+}
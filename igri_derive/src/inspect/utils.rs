@@ -2,34 +2,68 @@
 
 use darling::*;
 use proc_macro2::TokenStream as TokenStream2;
+use proc_macro_crate::FoundCrate;
 use quote::*;
 use syn::*;
 
-use crate::inspect::args;
+use crate::inspect::{args, bound};
 
-pub fn imgui_path() -> TokenStream2 {
-    quote!(igri::imgui)
+/// Path to the `igri` crate: `#[inspect(crate = "..")]` or the dependency name in `Cargo.toml`
+pub fn crate_path(ty_args: &args::TypeArgs) -> TokenStream2 {
+    if let Some(path) = ty_args.crate_.as_ref() {
+        return quote!(#path);
+    }
+
+    match proc_macro_crate::crate_name("igri") {
+        Ok(FoundCrate::Name(name)) => {
+            let name = format_ident!("{}", name);
+            quote!(::#name)
+        }
+        // `igri` itself (integration tests), or re-exported `igri` in scope
+        Ok(FoundCrate::Itself) | Err(_) => quote!(igri),
+    }
 }
 
-pub fn inspect_path() -> TokenStream2 {
-    quote!(igri::Inspect)
+pub fn imgui_path(ty_args: &args::TypeArgs) -> TokenStream2 {
+    let igri = self::crate_path(ty_args);
+    quote!(#igri::imgui)
+}
+
+pub fn inspect_path(ty_args: &args::TypeArgs) -> TokenStream2 {
+    let igri = self::crate_path(ty_args);
+    quote!(#igri::Inspect)
 }
 
 /// Code for `#[inspect(as = ..)]` in `inspect` function
-pub fn impl_inspect_as(x: TokenStream2, as_: &String) -> TokenStream2 {
+pub fn impl_inspect_as(
+    ty_args: &args::TypeArgs,
+    x: TokenStream2,
+    label: TokenStream2,
+    as_: &str,
+) -> TokenStream2 {
+    let inspect = self::inspect_path(ty_args);
     let as_ = parse_str::<Type>(as_).expect("#[inspect(as = ..)] must refer to a type");
     quote! {
         let mut bridge: #as_ = (*#x).into();
-        bridge.inspect(ui, label);
+        #inspect::inspect_ctx(&mut bridge, ctx, ui, #label);
         *#x = bridge.into();
     }
 }
 
 /// Code for `#[inspect(with = ..)]` in `inspect` function
-pub fn impl_inspect_with(x_ref: TokenStream2, with: &String) -> TokenStream2 {
-    if let Ok(with) = parse_str::<ExprPath>(with) {
+///
+/// The function may or may not take the `InspectCtx` (see `igri::InspectWith`).
+pub fn impl_inspect_with(
+    ty_args: &args::TypeArgs,
+    x_ref: TokenStream2,
+    label: TokenStream2,
+    with: &str,
+) -> TokenStream2 {
+    let igri = self::crate_path(ty_args);
+
+    if let Some(with) = self::with_path(with) {
         return quote! {
-            #with(#x_ref, ui, label);
+            #igri::inspect_with(#with, #x_ref, ctx, ui, #label);
         };
     }
 
@@ -43,19 +77,158 @@ pub fn impl_inspect_with(x_ref: TokenStream2, with: &String) -> TokenStream2 {
     panic!("invalid argument for #[inspect(with = ..)]");
 }
 
-/// `<prefix>field.inspect(ui, label);`
+/// Function path of `#[inspect(with = "..")]`
+pub fn with_path(with: &str) -> Option<ExprPath> {
+    parse_str::<ExprPath>(with).ok()
+}
+
+/// Collects `///` doc comments into a string
+pub fn doc_string(attrs: &[Attribute]) -> Option<String> {
+    let lines = attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("doc"))
+        .filter_map(|attr| match attr.parse_meta() {
+            Ok(Meta::NameValue(MetaNameValue {
+                lit: Lit::Str(s), ..
+            })) => Some(s.value()),
+            _ => None,
+        })
+        .map(|line| line.strip_prefix(' ').map(str::to_string).unwrap_or(line))
+        .collect::<Vec<_>>();
+
+    if lines.is_empty() {
+        None
+    } else {
+        Some(lines.join("\n").trim().to_string())
+    }
+}
+
+/// `#[inspect(tooltip = "..")]` or the doc comment with `#[inspect(doc_tooltips)]`
+pub fn tooltip(
+    ty_args: &args::TypeArgs,
+    tooltip: &Option<String>,
+    attrs: &[Attribute],
+) -> Option<String> {
+    tooltip.clone().or_else(|| {
+        if ty_args.doc_tooltips {
+            self::doc_string(attrs)
+        } else {
+            None
+        }
+    })
+}
+
+/// `Inspect::inspect_ctx(<prefix>field, ctx, ui, label);`
 pub fn field_inspectors<'a, T: ToTokens + 'a>(
+    ty_args: &'a args::TypeArgs,
     // field token → field token
     mut to_field_mut: impl FnMut(TokenStream2) -> T + 'a,
     field_args: &'a ast::Fields<args::FieldArgs>,
 ) -> impl Iterator<Item = TokenStream2> + 'a {
+    self::inspected_fields(field_args).map(move |(field_ident, label, field)| {
+        let segment = self::field_segment(&field_ident);
+        let field_mut = to_field_mut(field_ident);
+        let inspector =
+            self::field_inspector(ty_args, field, quote! { #field_mut }, quote! { #label });
+        let inspector = self::field_path(ty_args, &segment, inspector);
+        self::field_conditions(field, inspector)
+    })
+}
+
+/// `Inspect::visit` for each non-skipped field (fields with `#[inspect(with = ..)]` have no leaves)
+pub fn field_visitors<'a, T: ToTokens + 'a>(
+    ty_args: &'a args::TypeArgs,
+    mut to_field_mut: impl FnMut(TokenStream2) -> T + 'a,
+    field_args: &'a ast::Fields<args::FieldArgs>,
+) -> impl Iterator<Item = TokenStream2> + 'a {
+    let igri = self::crate_path(ty_args);
+
+    self::inspected_fields(field_args)
+        .filter(|(_, _, field)| field.with.is_none())
+        .map(move |(field_ident, label, field)| {
+            let segment = self::field_segment(&field_ident);
+            let field_mut = to_field_mut(field_ident);
+
+            // custom labels are visited for the search filter
+            let visit_field = |x: TokenStream2| match field.label.as_ref() {
+                Some(_) => quote!(#igri::visit::visit_labeled(visitor, #segment, #label, #x);),
+                None => quote!(#igri::visit::visit_field(visitor, #segment, #x);),
+            };
+
+            match field.as_.as_ref() {
+                Some(as_) => {
+                    let as_ =
+                        parse_str::<Type>(as_).expect("#[inspect(as = ..)] must refer to a type");
+                    let visit_bridge = visit_field(quote!(&mut bridge));
+                    quote! {
+                        {
+                            let mut bridge: #as_ = (*#field_mut).into();
+                            #visit_bridge
+                            *#field_mut = bridge.into();
+                        }
+                    }
+                }
+                None => visit_field(quote!(#field_mut)),
+            }
+        })
+}
+
+/// Path segment of the field: the field name or the tuple index
+pub fn field_segment(field_ident: &TokenStream2) -> String {
+    let s = field_ident.to_string();
+    s.strip_prefix("r#").map(str::to_string).unwrap_or(s)
+}
+
+/// Runs the field inspector with the field segment pushed to the current path and the ID stack
+pub fn field_path(
+    ty_args: &args::TypeArgs,
+    segment: &str,
+    inspector: TokenStream2,
+) -> TokenStream2 {
+    let igri = self::crate_path(ty_args);
+
+    quote! {
+        #igri::field(ctx, ui, #segment, |ctx| {
+            #inspector
+        });
+    }
+}
+
+/// Wraps the field inspector with `visible_if` and `enabled_if` conditions
+pub fn field_conditions(field: &args::FieldArgs, inspector: TokenStream2) -> TokenStream2 {
+    let inspector = match field.enabled_if.as_ref() {
+        Some(enabled_if) => quote! {
+            {
+                let _enabled = ui.begin_enabled(#enabled_if);
+                #inspector
+            }
+        },
+        None => inspector,
+    };
+
+    match field.visible_if.as_ref() {
+        Some(visible_if) => quote! {
+            if #visible_if {
+                #inspector
+            }
+        },
+        None => inspector,
+    }
+}
+
+/// Enumerates non-skipped fields as `(field_ident, label, field)`
+pub fn inspected_fields(
+    field_args: &ast::Fields<args::FieldArgs>,
+) -> impl Iterator<Item = (TokenStream2, String, &args::FieldArgs)> {
+    let style = field_args.style;
+
     field_args
         .fields
         .iter()
-        .filter(|field| !field.skip)
         .enumerate()
-        .map(move |(field_index, field)| {
-            let (field_ident, label) = match field_args.style {
+        .filter(|(_, field)| !field.skip)
+        .filter_map(move |(field_index, field)| {
+            let (field_ident, default_label) = match style {
                 ast::Style::Struct => {
                     let field_ident = field.ident.as_ref().unwrap_or_else(|| unreachable!());
                     (quote!(#field_ident), format!("{}", field_ident))
@@ -64,82 +237,219 @@ pub fn field_inspectors<'a, T: ToTokens + 'a>(
                     let field_ident = Index::from(field_index);
                     (quote!(#field_ident), format!("{}", field_index))
                 }
-                ast::Style::Unit => return quote! {},
+                ast::Style::Unit => return None,
             };
 
-            let field_mut = to_field_mut(field_ident);
-
-            if let Some(as_) = field.as_.as_ref() {
-                // #[inspect(as = "type")]
-                self::impl_inspect_as(quote! { #field_mut }, as_)
-            } else if let Some(with) = field.with.as_ref() {
-                // #[inspect(with = "function")]
-                self::impl_inspect_with(quote! { #field_mut }, with)
-            } else {
-                // inspect the value as-is
-                quote! {
-                    #field_mut.inspect(ui, #label);
-                }
-            }
+            let label = field.label.clone().unwrap_or(default_label);
+            Some((field_ident, label, field))
         })
 }
 
-/// Read-only current enum tag
-pub fn current_enum_tag(
+/// `Inspect::inspect_ctx(field_mut, ctx, ui, label);` with field attributes applied
+pub fn field_inspector(
     ty_args: &args::TypeArgs,
-    variant_args: &[args::VariantArgs],
+    field: &args::FieldArgs,
+    field_mut: TokenStream2,
+    label: TokenStream2,
 ) -> TokenStream2 {
-    let (v_idents, _indices, index_matchers) = self::enum_map(ty_args, variant_args);
+    let igri = self::crate_path(ty_args);
+    let inspect = self::inspect_path(ty_args);
+
+    let inspector = if let Some(as_) = field.as_.as_ref() {
+        // #[inspect(as = "type")]
+        self::impl_inspect_as(ty_args, field_mut.clone(), label.clone(), as_)
+    } else if let Some(with) = field.with.as_ref() {
+        // #[inspect(with = "function")]
+        self::impl_inspect_with(ty_args, field_mut.clone(), label.clone(), with)
+    } else if field.table {
+        // #[inspect(table)]
+        quote! {
+            #igri::table::table(#field_mut, ctx, ui, #label);
+        }
+    } else if field.multi_select {
+        // #[inspect(multi_select)]
+        quote! {
+            #igri::multi::multi_select(#field_mut, ctx, ui, #label);
+        }
+    } else if let Some(args::KeyField(key)) = field.key.as_ref() {
+        // #[inspect(key = "field")]
+        quote! {
+            #igri::seq_keyed(#field_mut, ctx, ui, #label, |x| &x.#key);
+        }
+    } else {
+        // inspect the value as-is
+        quote! {
+            #inspect::inspect_ctx(#field_mut, ctx, ui, #label);
+        }
+    };
+
+    let inspector = match self::tooltip(ty_args, &field.tooltip, &field.attrs) {
+        Some(tooltip) => quote! {
+            #igri::tooltip(ui, #tooltip, || {
+                #inspector
+            });
+        },
+        None => inspector,
+    };
+
+    let inspector = if field.min.is_some() || field.max.is_some() {
+        // grouped so that edits of any of the field's widgets are reported by the last item
+        quote! {
+            ui.group(|| {
+                #inspector
+            });
+        }
+    } else {
+        inspector
+    };
+
+    let validation = self::field_validation(ty_args, field, field_mut);
 
     quote! {
-        let name = {
-            const NAMES: &'static [&'static str] = &[
-                #(
-                    stringify!(#v_idents),
-                )*
-            ];
+        #inspector
+        #validation
+    }
+}
 
-            let ix = match self {
-                #(#index_matchers)*
-            };
+/// Code for `#[inspect(min = .., max = .., validate = ..)]` on fields
+fn field_validation(
+    ty_args: &args::TypeArgs,
+    field: &args::FieldArgs,
+    field_mut: TokenStream2,
+) -> TokenStream2 {
+    let igri = self::crate_path(ty_args);
 
-            NAMES[ix]
+    let clamp = if field.min.is_some() || field.max.is_some() {
+        let min_expr = field.min.as_ref().map(|x| &x.0);
+        let max_expr = field.max.as_ref().map(|x| &x.0);
+
+        let msg = match (min_expr, max_expr) {
+            (Some(min), Some(max)) => {
+                format!("must be in {}..={}", quote!(#min), quote!(#max))
+            }
+            (Some(min), None) => format!("must be >= {}", quote!(#min)),
+            (None, Some(max)) => format!("must be <= {}", quote!(#max)),
+            (None, None) => unreachable!(),
         };
 
-        ui.label_text(label, name);
+        let min = min_expr.map_or(quote!(None), |x| quote!(Some((#x) as _)));
+        let max = max_expr.map_or(quote!(None), |x| quote!(Some((#x) as _)));
+
+        // `with` and `as` fields may not implement `Inspect` themselves
+        let commit = if field.with.is_none() && field.as_.is_none() {
+            quote!(#igri::validate::commit(ctx, #field_mut);)
+        } else {
+            quote!()
+        };
+
+        quote! {
+            if #igri::validate::clamp_edit(ctx, ui, #field_mut, #min, #max, #msg) {
+                #commit
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    let validate = match field.validate.as_ref() {
+        Some(validate) => quote! {
+            if let Err(err) = #validate(&*#field_mut) {
+                #igri::validate::error(ui, err);
+            }
+        },
+        None => quote! {},
+    };
+
+    quote! {
+        #clamp
+        #validate
+    }
+}
+
+/// Code for `#[inspect(validate = ..)]` on types
+pub fn type_validation(ty_args: &args::TypeArgs) -> TokenStream2 {
+    let igri = self::crate_path(ty_args);
+
+    match ty_args.validate.as_ref() {
+        Some(validate) => quote! {
+            if let Err(err) = #validate(&*self) {
+                #igri::validate::type_error(ui, err);
+            }
+        },
+        None => quote! {},
     }
 }
 
-/// Select enum variant with default values
-pub fn enum_tag_selector<'a>(
+/// `igri::Tag` of the enum (`self`), read-only with `#[inspect(no_tag)]`
+pub fn enum_tag(ty_args: &args::TypeArgs, variant_args: &[args::VariantArgs]) -> TokenStream2 {
+    let igri = self::crate_path(ty_args);
+    let (v_idents, indices, index_matchers) = self::enum_map(ty_args, variant_args);
+
+    let set = if ty_args.no_tag {
+        quote!(None)
+    } else {
+        let default_variants = self::default_variants(ty_args, variant_args).collect::<Vec<_>>();
+        quote! {
+            Some(|x: &mut Self, ix: usize| {
+                *x = match ix {
+                    #(
+                        _ if ix == #indices => #default_variants,
+                    )*
+                    _ => unreachable!(),
+                }
+            })
+        }
+    };
+
+    quote! {
+        #igri::Tag {
+            value: self,
+            names: &[
+                #(
+                    stringify!(#v_idents),
+                )*
+            ],
+            index: |x: &Self| match x {
+                #(#index_matchers)*
+            },
+            set: #set,
+        }
+    }
+}
+
+/// Tag selector of the enum, or the read-only current tag with `#[inspect(no_tag)]`
+pub fn enum_tag_inspector(
     ty_args: &args::TypeArgs,
     variant_args: &[args::VariantArgs],
+    label: TokenStream2,
 ) -> TokenStream2 {
-    let (v_idents, indices, index_matchers) = self::enum_map(ty_args, variant_args);
-    let default_variants = self::default_variants(ty_args, &variant_args).collect::<Vec<_>>();
+    let inspect = self::inspect_path(ty_args);
+    let tag = self::enum_tag(ty_args, variant_args);
+    let tooltips = self::variant_tooltips(ty_args, variant_args);
 
     quote! {
-        const NAMES: &'static [&'static str] = &[
-            #(
-                stringify!(#v_idents),
-            )*
-        ];
-
-        let mut ix = match self {
-            #(#index_matchers)*
-        };
+        let mut tag = #tag;
+        #inspect::inspect_ctx(&mut tag, ctx, ui, #label);
+        let ix = (tag.index)(tag.value);
+        #tooltips
+    }
+}
 
-        if ui.combo_simple_string(
-            "tag",
-            &mut ix,
-            NAMES,
-        ) {
-            *self = match ix {
-                #(
-                    _ if ix == #indices => #default_variants,
-                )*
-                _ => unreachable!(),
-            }
+/// Tooltip of the current variant `ix` on hovering the tag
+fn variant_tooltips(ty_args: &args::TypeArgs, variant_args: &[args::VariantArgs]) -> TokenStream2 {
+    let tooltips = variant_args
+        .iter()
+        .map(|v| self::tooltip(ty_args, &v.tooltip, &v.attrs).unwrap_or_default())
+        .collect::<Vec<_>>();
+
+    if tooltips.iter().all(|t| t.is_empty()) {
+        return quote! {};
+    }
+
+    quote! {
+        const TOOLTIPS: &[&str] = &[#(#tooltips,)*];
+        if ui.is_item_hovered() && !TOOLTIPS[ix].is_empty() {
+            ui.tooltip_text(TOOLTIPS[ix]);
         }
     }
 }
@@ -239,97 +549,221 @@ fn default_variants<'a>(
     })
 }
 
-/// Fill the `inspect` function body to derive `Inspect`
+/// Fill the `inspect` and `visit` function bodies to derive `Inspect`
 pub fn impl_inspect(
     ty_args: &args::TypeArgs,
     generics: Generics,
     inspect_body: TokenStream2,
+    visit_body: TokenStream2,
 ) -> TokenStream2 {
     let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
 
     let ty_ident = &ty_args.ident;
 
-    let imgui = imgui_path();
-    let inspect = inspect_path();
+    // generic types keep the default, with the type parameters
+    let type_name = generics.params.is_empty().then(|| {
+        let name = ty_ident.to_string();
+        quote! {
+            fn inspect_type_name(&self) -> &'static str {
+                #name
+            }
+        }
+    });
+
+    let igri = crate_path(ty_args);
+    let imgui = imgui_path(ty_args);
+    let inspect = inspect_path(ty_args);
 
-    return quote! {
+    let inspect_id = ty_args.id.as_ref().map(|id| {
+        quote! {
+            fn inspect_id(&self) -> Option<u64> {
+                Some(#igri::hash_id(&#id))
+            }
+        }
+    });
+
+    quote! {
         impl #impl_generics #inspect for #ty_ident #ty_generics #where_clause
         {
-            fn inspect(&mut self, ui: &#imgui::Ui, label: &str) {
-                #inspect_body
+            fn inspect_ctx(&mut self, ctx: &mut #igri::InspectCtx, ui: &#imgui::Ui, label: &str) {
+                ctx.root(label, |ctx| {
+                    #inspect_body
+                });
+            }
+
+            #[allow(unused_variables)]
+            fn visit(&mut self, visitor: &mut dyn #igri::visit::Visitor) {
+                #visit_body
             }
+
+            #inspect_id
+
+            #type_name
         }
-    };
+    }
+}
+
+/// `impl Columns`: a column per field, except the ones with `with` or `as`
+pub fn impl_columns(
+    ty_args: &args::TypeArgs,
+    generics: Generics,
+    field_args: &ast::Fields<args::FieldArgs>,
+) -> TokenStream2 {
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let ty_ident = &ty_args.ident;
+
+    let igri = crate_path(ty_args);
+    let imgui = imgui_path(ty_args);
+    let inspect = inspect_path(ty_args);
+
+    let fields = self::inspected_fields(field_args)
+        .filter(|(_, _, field)| field.with.is_none() && field.as_.is_none())
+        .collect::<Vec<_>>();
+
+    let columns = fields.iter().map(|(field_ident, label, _field)| {
+        let segment = self::field_segment(field_ident);
+        quote!(#igri::table::Column { name: #segment, label: #label })
+    });
+
+    // cells run the same inspector as the fields, with validation and conditions
+    let cells = fields.iter().map(|(field_ident, label, field)| {
+        let cell_label = format!("##{}", label);
+        let inspector = self::field_inspector(
+            ty_args,
+            field,
+            quote!(&mut self.#field_ident),
+            quote!(#cell_label),
+        );
+        self::field_conditions(field, inspector)
+    });
+
+    let field_idents = fields.iter().map(|(field_ident, _, _)| field_ident);
+    let ixs = 0..fields.len();
+    let cell_ixs = 0..fields.len();
+
+    quote! {
+        impl #impl_generics #igri::table::Columns for #ty_ident #ty_generics #where_clause
+        {
+            fn columns() -> &'static [#igri::table::Column] {
+                &[#(#columns),*]
+            }
+
+            fn column_mut(&mut self, column: usize) -> &mut dyn #inspect {
+                match column {
+                    #(#ixs => &mut self.#field_idents,)*
+                    _ => panic!("column index out of range: {}", column),
+                }
+            }
+
+            fn inspect_column(
+                &mut self,
+                column: usize,
+                ctx: &mut #igri::InspectCtx,
+                ui: &#imgui::Ui,
+            ) {
+                match column {
+                    #(#cell_ixs => { #cells })*
+                    _ => panic!("column index out of range: {}", column),
+                }
+            }
+        }
+    }
+}
+
+/// `if let Some(_token) = igri::tree_node(..) { <body> }` with the context menu for `self`
+pub fn tree_node(ty_args: &args::TypeArgs, open: bool, body: TokenStream2) -> TokenStream2 {
+    let igri = self::crate_path(ty_args);
+
+    quote! {
+        if let Some(_token) = #igri::tree_node(
+            ctx,
+            ui,
+            label,
+            #open,
+            Some(#igri::menu::MenuTarget::new(self)),
+        ) {
+            #body
+        }
+    }
 }
 
 pub fn struct_inspect_generics(ty_args: &args::TypeArgs) -> Generics {
-    let mut generics = ty_args.generics.clone();
-    let clause = generics.make_where_clause();
+    let fields = ty_args.all_fields();
+    let generics = bound::with_field_bounds(&ty_args.generics, &fields);
 
     if let Some(bounds) = ty_args.bounds.as_ref() {
-        // add user's manual boundaries
-        if !bounds.is_empty() {
-            clause.predicates.extend(
-                bounds
-                    .split(",")
-                    .map(|b| parse_str::<WherePredicate>(b).unwrap()),
-            );
-        }
-    } else {
-        let inspect = inspect_path();
-
-        // add `Field: Inspect` for each field
-        clause.predicates.extend(
-            ty_args
-                .all_fields()
-                .iter()
-                .filter(|f| !f.skip && f.with.is_none())
-                .map(|f| &f.ty)
-                .map::<WherePredicate, _>(|ty| parse_quote! { #ty: #inspect }),
-        );
+        // user's manual boundaries
+        return bound::with_predicates(&generics, bounds.0.iter().cloned());
     }
 
-    generics
+    let inspect = inspect_path(ty_args);
+
+    // `T: Inspect` for each type parameter in the inspected fields
+    bound::with_bound(
+        &generics,
+        &fields,
+        |f| !f.skip && f.with.is_none() && f.as_.is_none(),
+        quote!(#inspect),
+    )
 }
 
 pub fn enum_inspect_generics(ty_args: &args::TypeArgs) -> Generics {
-    let mut generics = ty_args.generics.clone();
-    let inspect = inspect_path();
-
-    let clause = generics.make_where_clause();
+    let fields = ty_args.inferred_fields();
+    let generics = bound::with_predicates(&ty_args.generics, ty_args.variant_bounds());
+    let generics = bound::with_field_bounds(&generics, &fields);
 
     if let Some(bounds) = ty_args.bounds.as_ref() {
-        // add user's manual boundaries
-        if !bounds.is_empty() {
-            clause.predicates.extend(
-                bounds
-                    .split(",")
-                    .map(|b| parse_str::<WherePredicate>(b).unwrap()),
-            );
-        }
+        // user's manual boundaries
+        return bound::with_predicates(&generics, bounds.0.iter().cloned());
+    }
+
+    let inspect = inspect_path(ty_args);
+
+    // `T: Inspect` for each type parameter in the inspected fields
+    let generics = bound::with_bound(
+        &generics,
+        &fields,
+        |f| !f.skip && f.with.is_none() && f.as_.is_none(),
+        quote!(#inspect),
+    );
+
+    if ty_args.no_tag {
+        generics
     } else {
-        if ty_args.no_tag {
-            // add `Field: Inspect + Default` for each field
-            clause.predicates.extend(
-                ty_args
-                    .all_fields()
-                    .iter()
-                    .filter(|f| !f.skip)
-                    .map(|f| &f.ty)
-                    .map::<WherePredicate, _>(|ty| parse_quote! { #ty: #inspect  }),
-            );
+        // `T: Default` for each type parameter in the inspected fields, for the tag selector
+        let generics = bound::with_bound(&generics, &fields, |f| !f.skip, quote!(Default));
+        // skipped fields are only constructed with `Default::default()`
+        bound::with_field_type_bound(&generics, &fields, |f| f.skip, quote!(Default))
+    }
+}
+
+/// Code for `#[inspect(button(..))]` on types
+pub fn type_buttons(ty_args: &args::TypeArgs) -> TokenStream2 {
+    let igri = self::crate_path(ty_args);
+
+    let buttons = ty_args.buttons.iter().enumerate().map(|(i, button)| {
+        let label = &button.label;
+        let method = &button.method;
+        let confirm = match button.confirm.as_ref() {
+            Some(msg) => quote!(Some(#msg)),
+            None => quote!(None),
+        };
+        let same_line = if i == 0 {
+            quote! {}
         } else {
-            // add `Field: Inspect + Default` for each field
-            clause.predicates.extend(
-                ty_args
-                    .all_fields()
-                    .iter()
-                    .filter(|f| !f.skip)
-                    .map(|f| &f.ty)
-                    .map::<WherePredicate, _>(|ty| parse_quote! { #ty: #inspect + Default }),
-            );
+            quote! { ui.same_line(); }
+        };
+
+        quote! {
+            #same_line
+            if #igri::button(ui, #label, #confirm) {
+                self.#method();
+            }
         }
-    }
+    });
 
-    generics
+    quote! {
+        #(#buttons)*
+    }
 }
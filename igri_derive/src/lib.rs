@@ -5,7 +5,10 @@ use proc_macro::TokenStream;
 use syn::{parse_macro_input, DeriveInput};
 
 /**
-Derive macro for the `Inspect` trait. NOTE: `igri` must be in scope to use this macro.
+Derive macro for the `Inspect` trait
+
+The path to `igri` is resolved from your `Cargo.toml`. If you depend on `igri` only through a
+re-export, specify it with `#[inspect(crate = "engine::igri")]`.
 
 # Attributes
 